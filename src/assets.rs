@@ -0,0 +1,64 @@
+// src/assets.rs
+
+use std::collections::HashMap;
+use crate::obj::{Obj, MeshBounds};
+use crate::vertex::Vertex;
+
+/// Malla lista para dibujar: el arreglo de vértices ya desindexado (tres por triángulo) y sus
+/// límites se calculan una sola vez al cargarla, no en cada cuadro
+pub struct MeshAsset {
+    vertices: Vec<Vertex>,
+    pub bounds: MeshBounds,
+}
+
+impl MeshAsset {
+    pub fn new(obj: &Obj) -> Self {
+        MeshAsset {
+            vertices: obj.get_vertex_array(),
+            bounds: obj.bounds(),
+        }
+    }
+
+    pub fn vertices(&self) -> &[Vertex] {
+        &self.vertices
+    }
+}
+
+/// Referencia a una malla del `AssetManager`; copiarla no copia la malla
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct MeshHandle(usize);
+
+/// Mallas de la escena, compartidas entre los cuerpos que las usan. Cada archivo se lee una
+/// sola vez aunque se pida varias veces.
+#[derive(Default)]
+pub struct AssetManager {
+    meshes: Vec<MeshAsset>,
+    loaded: HashMap<String, MeshHandle>, // Ruta del archivo -> malla ya cargada
+}
+
+impl AssetManager {
+    pub fn new() -> Self {
+        AssetManager::default()
+    }
+
+    /// Carga un OBJ, o devuelve la malla ya cargada si esa ruta se pidió antes
+    pub fn load_mesh(&mut self, path: &str) -> Result<MeshHandle, String> {
+        if let Some(&handle) = self.loaded.get(path) {
+            return Ok(handle);
+        }
+        let obj = Obj::load(path).map_err(|e| format!("Failed to load mesh {}: {}", path, e))?;
+        let handle = self.add_mesh(&obj);
+        self.loaded.insert(path.to_string(), handle);
+        Ok(handle)
+    }
+
+    /// Registra una malla generada, como las icoesferas de `LodChain`
+    pub fn add_mesh(&mut self, obj: &Obj) -> MeshHandle {
+        self.meshes.push(MeshAsset::new(obj));
+        MeshHandle(self.meshes.len() - 1)
+    }
+
+    pub fn mesh(&self, handle: MeshHandle) -> &MeshAsset {
+        &self.meshes[handle.0]
+    }
+}
@@ -0,0 +1,142 @@
+// src/atmosphere.rs
+
+use nalgebra_glm::{Vec3, dot};
+use crate::lighting::{PointLight, smoothstep};
+
+/// Muestras a lo largo del rayo de vista dentro de la atmósfera
+const VIEW_SAMPLES: usize = 8;
+
+/// Parámetros de dispersión de la atmósfera de un cuerpo.
+/// Las distancias están en radios del planeta, así que no dependen de su escala.
+#[derive(Clone, Copy, Debug)]
+pub struct Atmosphere {
+    pub thickness: f32,     // Grosor de la capa (fracción del radio del planeta)
+    pub scale_height: f32,  // Altura de escala de la densidad (fracción del radio)
+    pub density: f32,       // Multiplicador global de densidad
+    pub rayleigh: Vec3,     // Coeficientes de Rayleigh por canal (RGB): definen el color del cielo
+    pub mie: f32,           // Coeficiente de Mie (polvo/aerosoles, gris)
+    pub mie_g: f32,         // Anisotropía de Mie (0 = isótropo, ~0.8 = hacia adelante)
+}
+
+impl Atmosphere {
+    /// Cielo azul tipo Tierra
+    pub fn earth_like() -> Self {
+        Atmosphere {
+            thickness: 0.1,
+            scale_height: 0.025,
+            density: 1.0,
+            rayleigh: Vec3::new(0.65, 1.5, 3.7),
+            mie: 0.3,
+            mie_g: 0.76,
+        }
+    }
+
+    /// Atmósfera turquesa, algo más densa
+    pub fn teal() -> Self {
+        Atmosphere {
+            thickness: 0.12,
+            scale_height: 0.03,
+            density: 1.2,
+            rayleigh: Vec3::new(0.5, 2.2, 2.4),
+            mie: 0.25,
+            mie_g: 0.7,
+        }
+    }
+
+    /// Atmósfera fina y polvorienta tipo Marte: domina Mie y el cielo tira a ocre
+    pub fn dusty() -> Self {
+        Atmosphere {
+            thickness: 0.06,
+            scale_height: 0.02,
+            density: 0.6,
+            rayleigh: Vec3::new(1.6, 1.0, 0.55),
+            mie: 1.2,
+            mie_g: 0.65,
+        }
+    }
+}
+
+/// Atmósfera colocada alrededor de un planeta concreto en el espacio del mundo
+#[derive(Clone, Copy, Debug)]
+pub struct AtmosphereShell {
+    pub params: Atmosphere,
+    pub planet_center: Vec3,
+    pub planet_radius: f32,
+}
+
+impl AtmosphereShell {
+    /// Luz dispersada hacia la cámara a lo largo del rayo `eye + t * dir` (dispersión simple).
+    /// Devuelve RGB lineal, para sumarlo sobre lo que ya hay en el framebuffer.
+    pub fn scatter(&self, eye: &Vec3, dir: &Vec3, light: &PointLight) -> Vec3 {
+        let params = &self.params;
+        // Todo se calcula en radios del planeta, centrado en el planeta
+        let origin = (eye - self.planet_center) / self.planet_radius;
+        let outer = 1.0 + params.thickness;
+
+        let Some((t_enter, mut t_exit)) = ray_sphere(&origin, dir, outer) else {
+            return Vec3::zeros();
+        };
+        // El planeta tapa el resto del rayo
+        if let Some((t_ground, _)) = ray_sphere(&origin, dir, 1.0) {
+            if t_ground > 0.0 {
+                t_exit = t_exit.min(t_ground);
+            }
+        }
+        let t_enter = t_enter.max(0.0);
+        if t_exit <= t_enter {
+            return Vec3::zeros();
+        }
+
+        let light_dir = (light.position - self.planet_center).normalize();
+        let mu = dot(dir, &light_dir);
+        let phase_rayleigh = 0.75 * (1.0 + mu * mu);
+        let phase_mie = henyey_greenstein(mu, params.mie_g);
+
+        let step = (t_exit - t_enter) / VIEW_SAMPLES as f32;
+        let extinction = params.rayleigh + Vec3::repeat(params.mie * 1.1);
+        let mut view_depth = Vec3::zeros();
+        let mut inscattered = Vec3::zeros();
+
+        for i in 0..VIEW_SAMPLES {
+            let sample = origin + dir * (t_enter + step * (i as f32 + 0.5));
+            let radius = sample.magnitude();
+            let density = params.density * (-(radius - 1.0).max(0.0) / params.scale_height).exp();
+            view_depth += extinction * (density * step);
+
+            // Ángulo cenital del sol en la muestra: el sol se pone al cruzar el terminador
+            let cos_zenith = dot(&(sample / radius), &light_dir);
+            let sunlit = smoothstep(-0.2, 0.05, cos_zenith);
+            if sunlit <= 0.0 {
+                continue;
+            }
+
+            // Profundidad óptica hacia el sol (aproximación de Chapman): enrojece el terminador
+            let airmass = params.scale_height / (cos_zenith.max(0.0) + 0.15);
+            let sun_depth = extinction * (density * airmass);
+            let transmittance = (-(view_depth + sun_depth)).map(f32::exp);
+
+            let scattering = params.rayleigh * phase_rayleigh + Vec3::repeat(params.mie * phase_mie);
+            inscattered += scattering.component_mul(&transmittance) * (density * step * sunlit);
+        }
+
+        inscattered * light.exposure_at(&self.planet_center)
+    }
+}
+
+/// Función de fase de Henyey-Greenstein normalizada a media 1 (sin el factor 1/4π)
+pub fn henyey_greenstein(mu: f32, g: f32) -> f32 {
+    let g2 = g * g;
+    (1.0 - g2) / (1.0 + g2 - 2.0 * g * mu).max(1e-4).powf(1.5)
+}
+
+/// Intersección de un rayo con una esfera centrada en el origen: (t_cercano, t_lejano)
+fn ray_sphere(origin: &Vec3, dir: &Vec3, radius: f32) -> Option<(f32, f32)> {
+    let b = dot(origin, dir);
+    let c = origin.magnitude_squared() - radius * radius;
+    let discriminant = b * b - c;
+    if discriminant < 0.0 {
+        return None;
+    }
+    let root = discriminant.sqrt();
+    Some((-b - root, -b + root))
+}
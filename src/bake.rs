@@ -0,0 +1,348 @@
+// src/bake.rs
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use image::{Rgba, RgbaImage};
+use nalgebra_glm::{Vec3, Mat4};
+use crate::body_uniforms;
+use crate::assets::MeshAsset;
+use crate::color::Color;
+use crate::fragment::CelestialType;
+use crate::lighting::PointLight;
+use crate::material::TerrainMaterial;
+use crate::orbital::{BodyManager, CelestialBody};
+use crate::shaders::{terrain_object_normal, terrain_surface};
+use crate::shadows::ShadowCasters;
+use crate::texture::{SurfaceTextures, Texture};
+use crate::triangle::{barycentric_coordinates, edge_function};
+use crate::uniforms::{SceneLighting, Uniforms, ViewTransform};
+use crate::vertex::Vertex;
+
+/// Versión del horneado: cambiarla invalida las cachés anteriores (por ejemplo, si cambia
+/// `terrain_surface` sin que cambien los parámetros de los cuerpos)
+const BAKE_VERSION: u32 = 1;
+/// Puntos donde se muestrea el ruido para reconocer su configuración en la clave
+const NOISE_PROBES: usize = 32;
+
+/// Material de terreno de un cuerpo evaluado una vez por texel en el espacio UV de `mesh`
+/// (equirectangular en las esferas), en mapas de `width` x `width / 2` listos para `SurfaceTextures`
+pub struct TerrainBake {
+    pub albedo: RgbaImage,
+    pub specular: RgbaImage,
+    pub normal: RgbaImage,
+    pub night: Option<RgbaImage>,
+}
+
+impl TerrainBake {
+    pub fn new(body: &CelestialBody, mesh: &MeshAsset, width: u32) -> Self {
+        let material = body.terrain.unwrap_or_else(TerrainMaterial::earth_like);
+        let uniforms = bake_uniforms(body);
+        let (width, height) = (width.max(2), (width / 2).max(1));
+
+        // Se rasteriza la malla en el espacio UV: cada texel recibe la posición y la normal
+        // del modelo que verá el fragmento que lo muestree
+        let mut samples: Vec<Option<(Vec3, Vec3)>> = vec![None; (width * height) as usize];
+        for triangle in mesh.vertices().chunks_exact(3) {
+            rasterize_uv(triangle, width, height, &mut samples);
+        }
+        fill_gaps(&mut samples, width as usize, height as usize);
+
+        let mut albedo = RgbaImage::new(width, height);
+        let mut specular = RgbaImage::new(width, height);
+        let mut normal = RgbaImage::new(width, height);
+        let mut night = material.city_lights.map(|_| RgbaImage::new(width, height));
+
+        for (index, sample) in samples.iter().enumerate() {
+            let Some((position, object_normal)) = sample else {
+                continue;
+            };
+            let (x, y) = (index as u32 % width, index as u32 / width);
+            let surface = terrain_surface(&uniforms, &material, position);
+
+            albedo.put_pixel(x, y, color_pixel(surface.albedo));
+            let shine = (surface.specular.clamp(0.0, 1.0) * 255.0).round() as u8;
+            specular.put_pixel(x, y, Rgba([shine, shine, shine, 255]));
+
+            let tilted = if material.bump_strength > 0.0 {
+                terrain_object_normal(&uniforms, position, object_normal, surface.height, material.bump_strength)
+            } else {
+                *object_normal
+            };
+            normal.put_pixel(x, y, tangent_pixel(object_normal, &tilted));
+
+            if let Some(night) = night.as_mut() {
+                night.put_pixel(x, y, color_pixel(surface.city_lights));
+            }
+        }
+
+        TerrainBake { albedo, specular, normal, night }
+    }
+
+    /// Texturas con mipmaps que sustituyen por completo al material procedural
+    pub fn to_textures(&self) -> SurfaceTextures {
+        let textures = SurfaceTextures::new(1.0)
+            .with_albedo(Arc::new(Texture::from_image(&self.albedo)))
+            .with_specular(Arc::new(Texture::from_image(&self.specular)))
+            .with_normal(Arc::new(Texture::from_image(&self.normal)), 1.0);
+        match &self.night {
+            Some(night) => textures.with_night(Arc::new(Texture::from_image(night))),
+            None => textures,
+        }
+    }
+
+    /// Guarda los mapas con los nombres que espera `SurfaceTextures::load_dir`
+    fn save(&self, directory: &Path) -> Result<(), String> {
+        std::fs::create_dir_all(directory)
+            .map_err(|e| format!("Failed to create {}: {}", directory.display(), e))?;
+        let maps = [("albedo", Some(&self.albedo)), ("specular", Some(&self.specular)), ("normal", Some(&self.normal)), ("night", self.night.as_ref())];
+        for (name, image) in maps {
+            if let Some(image) = image {
+                let path = directory.join(format!("{}.png", name));
+                image.save(&path).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Caché en disco de los mapas horneados, con una carpeta por cuerpo cuyo nombre lleva
+/// la clave de sus parámetros: si alguno cambia, la clave cambia y se vuelve a hornear
+pub struct BakeCache {
+    directory: PathBuf,
+}
+
+impl BakeCache {
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        BakeCache { directory: directory.into() }
+    }
+
+    /// Carpeta de caché del usuario (`$XDG_CACHE_HOME`, `%LOCALAPPDATA%` o `~/.cache`), o la
+    /// temporal del sistema si no hay ninguna. Es una ruta de tiempo de ejecución: un binario
+    /// instalado o movido no escribe en el árbol de fuentes.
+    pub fn default_dir() -> PathBuf {
+        let user_cache = std::env::var_os("XDG_CACHE_HOME")
+            .or_else(|| std::env::var_os("LOCALAPPDATA"))
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")));
+        user_cache
+            .unwrap_or_else(std::env::temp_dir)
+            .join("space-travel")
+            .join("bakes")
+    }
+
+    /// Hornea los planetas rocosos que no tengan ya texturas propias y les asigna los mapas
+    pub fn bake_bodies(&self, body_manager: &mut BodyManager, width: u32) -> Result<(), String> {
+        for body in body_manager.all_bodies.iter_mut() {
+            if body.shader_type == CelestialType::Planet && body.textures.is_none() {
+                let mesh = body_manager.assets.mesh(body.detailed_mesh());
+                body.textures = Some(self.load_or_bake(body, mesh, width)?);
+            }
+        }
+        Ok(())
+    }
+
+    /// Mapas del cuerpo leídos de la caché, u horneados y guardados si no están o están incompletos
+    pub fn load_or_bake(&self, body: &CelestialBody, mesh: &MeshAsset, width: u32) -> Result<SurfaceTextures, String> {
+        let material = body.terrain.unwrap_or_else(TerrainMaterial::earth_like);
+        let prefix = format!("{}-", body.name.to_lowercase());
+        let directory = self.directory.join(format!("{}{:016x}", prefix, bake_key(body, mesh, width)));
+
+        // Un archivo corrupto o a medio escribir cuenta como que no está
+        if let Ok(Some(textures)) = SurfaceTextures::load_dir(&directory, 1.0) {
+            if textures.replaces(&material) {
+                return Ok(textures);
+            }
+        }
+
+        let bake = TerrainBake::new(body, mesh, width);
+        self.remove_stale(&prefix, &directory);
+        bake.save(&directory)?;
+        Ok(bake.to_textures())
+    }
+
+    /// Borra los horneados anteriores del mismo cuerpo, que ya no se usarán
+    fn remove_stale(&self, prefix: &str, current: &Path) {
+        let Ok(entries) = std::fs::read_dir(&self.directory) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let stale = entry.file_name().to_string_lossy().starts_with(prefix) && path != current;
+            if stale && path.is_dir() {
+                let _ = std::fs::remove_dir_all(&path);
+            }
+        }
+    }
+}
+
+/// Uniformes del cuerpo en su propio espacio: el horneado solo usa el ruido y los umbrales
+fn bake_uniforms(body: &CelestialBody) -> Uniforms {
+    body_uniforms(
+        body,
+        Vec3::zeros(),
+        SceneLighting::new(PointLight::new(Vec3::zeros(), Color::black(), 0.0, 1.0), Vec3::zeros(), ShadowCasters::default()),
+        ViewTransform::new(Mat4::identity(), Mat4::identity(), Mat4::identity()),
+        0.0,
+    )
+}
+
+/// Clave de los parámetros que influyen en el horneado. El ruido no se puede comparar
+/// directamente, así que se identifica por sus valores en unos puntos fijos; la malla, por
+/// sus vértices, que deciden dónde cae cada texel.
+fn bake_key(body: &CelestialBody, mesh: &MeshAsset, width: u32) -> u64 {
+    let mut hash = Fnv1a::new();
+    let parameters = format!(
+        "{} {} {:?} {:?}",
+        BAKE_VERSION,
+        width,
+        body.terrain,
+        body.thresholds,
+    );
+    hash.write(parameters.as_bytes());
+    for vertex in mesh.vertices() {
+        for value in vertex.position.iter().chain(vertex.normal.iter()).chain(vertex.tex_coords.iter()) {
+            hash.write_f32(*value);
+        }
+    }
+    for probe in 0..NOISE_PROBES {
+        let t = probe as f32;
+        hash.write_f32(body.noise.get_noise_3d(t * 1.37 - 20.0, t * 0.71 + 3.0, t * -2.13 + 11.0));
+    }
+    hash.finish()
+}
+
+/// Hash FNV-1a de 64 bits: estable entre compilaciones, a diferencia de `DefaultHasher`
+struct Fnv1a(u64);
+
+impl Fnv1a {
+    fn new() -> Self {
+        Fnv1a(0xcbf29ce484222325)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = (self.0 ^ byte as u64).wrapping_mul(0x100000001b3);
+        }
+    }
+
+    /// Los bits del valor, en el mismo orden de bytes en cualquier máquina
+    fn write_f32(&mut self, value: f32) {
+        self.write(&value.to_bits().to_le_bytes());
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+/// Escribe en `samples` la posición y la normal interpoladas de los texels cuyo centro cae
+/// dentro del triángulo en el espacio UV
+fn rasterize_uv(triangle: &[Vertex], width: u32, height: u32, samples: &mut [Option<(Vec3, Vec3)>]) {
+    let corners: Vec<Vec3> = triangle
+        .iter()
+        .map(|vertex| Vec3::new(vertex.tex_coords.x * width as f32, vertex.tex_coords.y * height as f32, 0.0))
+        .collect();
+    let area = edge_function(&corners[0], &corners[1], &corners[2]);
+    if area.abs() < 1e-9 {
+        return;
+    }
+
+    let min_x = corners.iter().map(|corner| corner.x).fold(f32::MAX, f32::min).floor().max(0.0) as u32;
+    let min_y = corners.iter().map(|corner| corner.y).fold(f32::MAX, f32::min).floor().max(0.0) as u32;
+    // Los triángulos que cruzan el meridiano donde `u` vuelve a 0 pasan de `width`: esa parte
+    // se escribe al principio de la fila
+    let max_x = (corners.iter().map(|corner| corner.x).fold(f32::MIN, f32::max).ceil() as u32).min(width * 2);
+    let max_y = (corners.iter().map(|corner| corner.y).fold(f32::MIN, f32::max).ceil() as u32).min(height);
+
+    for y in min_y..max_y {
+        for x in min_x..max_x {
+            let center = Vec3::new(x as f32 + 0.5, y as f32 + 0.5, 0.0);
+            let (w1, w2, w3) = barycentric_coordinates(&center, &corners[0], &corners[1], &corners[2], area);
+            if w1 < -1e-4 || w2 < -1e-4 || w3 < -1e-4 {
+                continue;
+            }
+            let position = triangle[0].position * w1 + triangle[1].position * w2 + triangle[2].position * w3;
+            let normal = (triangle[0].normal * w1 + triangle[1].normal * w2 + triangle[2].normal * w3).normalize();
+            samples[(y * width + x % width) as usize] = Some((position, normal));
+        }
+    }
+}
+
+/// Los texels que ningún triángulo cubre (junto a los polos, donde la malla deja huecos en
+/// el espacio UV) copian el texel cubierto más cercano de su misma columna
+fn fill_gaps(samples: &mut [Option<(Vec3, Vec3)>], width: usize, height: usize) {
+    for x in 0..width {
+        let column: Vec<Option<(Vec3, Vec3)>> = (0..height).map(|y| samples[y * width + x]).collect();
+        for y in 0..height {
+            if column[y].is_some() {
+                continue;
+            }
+            let nearest = (1..height).find_map(|offset| {
+                let above = y.checked_sub(offset).and_then(|y| column[y]);
+                let below = column.get(y + offset).copied().flatten();
+                above.or(below)
+            });
+            samples[y * width + x] = nearest;
+        }
+    }
+}
+
+fn color_pixel(color: Color) -> Rgba<u8> {
+    Rgba([color.r, color.g, color.b, 255])
+}
+
+/// Codifica una normal del modelo en el espacio tangente de la esfera (x al este, y al
+/// norte), como la lee `mapped_normal`
+fn tangent_pixel(surface_normal: &Vec3, normal: &Vec3) -> Rgba<u8> {
+    let east = Vec3::new(0.0, 1.0, 0.0).cross(surface_normal);
+    let east = if east.magnitude_squared() < 1e-8 { Vec3::new(0.0, 0.0, -1.0) } else { east.normalize() };
+    let north = surface_normal.cross(&east);
+
+    let encode = |value: f32| ((value.clamp(-1.0, 1.0) * 0.5 + 0.5) * 255.0).round() as u8;
+    Rgba([encode(normal.dot(&east)), encode(normal.dot(&north)), encode(normal.dot(surface_normal)), 255])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::create_body_manager;
+    use crate::noise::create_noise_moon;
+
+    #[test]
+    fn bake_key_is_stable_and_tracks_every_input() {
+        let body_manager = create_body_manager();
+        let planet = body_manager.get_body_by_name("Planet").unwrap();
+        let mesh = body_manager.assets.mesh(planet.detailed_mesh());
+        let key = bake_key(planet, mesh, 512);
+        assert_eq!(key, bake_key(&planet.clone(), mesh, 512));
+
+        assert_ne!(key, bake_key(planet, mesh, 1024), "width");
+
+        let mut thresholds = planet.clone();
+        thresholds.thresholds.ocean += 0.01;
+        assert_ne!(key, bake_key(&thresholds, mesh, 512), "thresholds");
+
+        let mut terrain = planet.clone();
+        terrain.terrain = Some(TerrainMaterial::mars_like());
+        assert_ne!(key, bake_key(&terrain, mesh, 512), "terrain");
+
+        let mut noise = planet.clone();
+        noise.noise = create_noise_moon();
+        assert_ne!(key, bake_key(&noise, mesh, 512), "noise");
+
+        let coarse = body_manager.assets.mesh(planet.lods.level(0));
+        assert_ne!(key, bake_key(planet, coarse, 512), "mesh");
+    }
+
+    #[test]
+    fn fnv1a_matches_the_reference_values() {
+        // Vectores de prueba publicados para FNV-1a de 64 bits
+        assert_eq!(Fnv1a::new().finish(), 0xcbf2_9ce4_8422_2325);
+        let mut hash = Fnv1a::new();
+        hash.write(b"a");
+        assert_eq!(hash.finish(), 0xaf63_dc4c_8601_ec8c);
+        let mut hash = Fnv1a::new();
+        hash.write(b"foobar");
+        assert_eq!(hash.finish(), 0x8594_4171_f739_67e8);
+    }
+}
@@ -0,0 +1,120 @@
+// src/billboard.rs
+
+use nalgebra_glm::{Vec3, Vec4, Mat4};
+use crate::color::Color;
+use crate::fragment::BlendMode;
+use crate::framebuffer::Framebuffer;
+
+/// Tamaño máximo en píxeles de un billboard, para que uno pegado a la cámara no llene la pantalla
+const MAX_SCREEN_RADIUS: f32 = 256.0;
+
+/// Disco orientado siempre hacia la cámara, con caída gaussiana desde el centro
+#[derive(Clone, Copy, Debug)]
+pub struct Billboard {
+    pub position: Vec3, // Centro en el espacio del mundo
+    pub radius: f32,    // Radio en unidades del mundo
+    pub color: Color,
+    pub intensity: f32, // Brillo (aditivo) u opacidad (alfa) en el centro
+}
+
+impl Billboard {
+    pub fn new(position: Vec3, radius: f32, color: Color, intensity: f32) -> Self {
+        Billboard {
+            position,
+            radius,
+            color,
+            intensity,
+        }
+    }
+}
+
+/// Dibuja los billboards con prueba de profundidad. Con mezcla aditiva o emisiva se suman
+/// al color o a la emisión y el orden no importa; con mezcla alfa se ordenan de atrás hacia delante.
+/// En modo opaco cada billboard es un disco sólido que escribe profundidad.
+pub fn render_billboards(
+    framebuffer: &mut Framebuffer,
+    billboards: &[Billboard],
+    blend_mode: BlendMode,
+    view_matrix: &Mat4,
+    projection_matrix: &Mat4,
+    viewport_matrix: &Mat4,
+) {
+    let view_projection = projection_matrix * view_matrix;
+    // Píxeles por unidad del mundo a distancia 1 de la cámara
+    let pixels_per_unit = projection_matrix[(1, 1)] * viewport_matrix[(1, 1)].abs();
+
+    let mut sorted;
+    let billboards = if blend_mode == BlendMode::Alpha {
+        // En el espacio de la vista la cámara mira hacia -z: lo más lejano tiene la z menor
+        let view_depth = |billboard: &Billboard| (view_matrix * Vec4::new(billboard.position.x, billboard.position.y, billboard.position.z, 1.0)).z;
+        sorted = billboards.to_vec();
+        sorted.sort_by(|a, b| view_depth(a).total_cmp(&view_depth(b)));
+        &sorted[..]
+    } else {
+        billboards
+    };
+
+    for billboard in billboards {
+        if billboard.intensity <= 0.0 {
+            continue;
+        }
+        let position = billboard.position;
+        let clip = view_projection * Vec4::new(position.x, position.y, position.z, 1.0);
+        // Detrás de la cámara
+        if clip.w <= 0.0 {
+            continue;
+        }
+        let ndc = clip.xyz() / clip.w;
+        let screen = viewport_matrix * Vec4::new(ndc.x, ndc.y, ndc.z, 1.0);
+        let radius = (billboard.radius * pixels_per_unit / clip.w).min(MAX_SCREEN_RADIUS);
+
+        // Un billboard menor que un píxel sigue aportando su luz, repartida en ese píxel
+        let (radius, intensity) = if radius < 1.0 {
+            (1.0, billboard.intensity * radius * radius)
+        } else {
+            (radius, billboard.intensity)
+        };
+
+        let min_x = (screen.x - radius).floor().max(0.0) as usize;
+        let min_y = (screen.y - radius).floor().max(0.0) as usize;
+        let max_x = (screen.x + radius).ceil().min(framebuffer.width as f32 - 1.0);
+        let max_y = (screen.y + radius).ceil().min(framebuffer.height as f32 - 1.0);
+        if max_x < 0.0 || max_y < 0.0 {
+            continue;
+        }
+
+        for y in min_y..=max_y as usize {
+            for x in min_x..=max_x as usize {
+                let dx = (x as f32 + 0.5 - screen.x) / radius;
+                let dy = (y as f32 + 0.5 - screen.y) / radius;
+                let distance_squared = dx * dx + dy * dy;
+                if distance_squared >= 1.0 {
+                    continue;
+                }
+                let falloff = intensity * (-4.0 * distance_squared).exp();
+                match blend_mode {
+                    BlendMode::Additive | BlendMode::Emissive => {
+                        let color = billboard.color * falloff;
+                        if color.is_black() {
+                            continue;
+                        }
+                        framebuffer.set_current_color(color.to_hex());
+                        if blend_mode == BlendMode::Emissive {
+                            framebuffer.add_emission(x, y, ndc.z);
+                        } else {
+                            framebuffer.add_point(x, y, ndc.z);
+                        }
+                    }
+                    BlendMode::Alpha => {
+                        framebuffer.set_current_color(billboard.color.to_hex());
+                        framebuffer.blend_point(x, y, ndc.z, falloff);
+                    }
+                    BlendMode::Opaque => {
+                        framebuffer.set_current_color(billboard.color.to_hex());
+                        framebuffer.point(x, y, ndc.z, 0x000000);
+                    }
+                }
+            }
+        }
+    }
+}
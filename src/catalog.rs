@@ -0,0 +1,147 @@
+// src/catalog.rs
+
+use std::path::Path;
+use nalgebra_glm::Vec3;
+use crate::color::Color;
+use crate::star::Star;
+
+/// Oblicuidad de la eclíptica (J2000): el plano de las órbitas está inclinado este ángulo
+/// respecto al ecuador celeste en el que vienen las coordenadas del catálogo
+const OBLIQUITY: f32 = 23.439_28 * std::f32::consts::PI / 180.0;
+
+/// Columnas del CSV del catálogo, en orden
+const COLUMNS: [&str; 5] = ["name", "ra_hours", "dec_degrees", "magnitude", "color_index"];
+
+/// Lee un catálogo de estrellas brillantes desde un CSV con columnas
+/// `name,ra_hours,dec_degrees,magnitude,color_index` (coordenadas ecuatoriales J2000 e índice
+/// B-V). La cabecera es opcional. Se ignoran las líneas vacías y las que empiezan con `#`.
+pub fn load_catalog(path: &Path) -> Result<Vec<Star>, String> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    contents
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .enumerate()
+        // Solo se salta la primera línea útil si es la cabecera; si no, ya es una estrella
+        .filter(|(row, (_, line))| *row > 0 || !is_header(line))
+        .map(|(_, (number, line))| {
+            parse_star(line).ok_or_else(|| format!("Invalid star at {}:{}: {}", path.display(), number, line))
+        })
+        .collect()
+}
+
+fn is_header(line: &str) -> bool {
+    line.split(',').map(str::trim).eq(COLUMNS)
+}
+
+fn parse_star(line: &str) -> Option<Star> {
+    let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+    let [_name, ra_hours, dec_degrees, magnitude, color_index] = fields[..] else {
+        return None;
+    };
+    let right_ascension = ra_hours.parse::<f32>().ok()? * std::f32::consts::PI / 12.0;
+    let declination = dec_degrees.parse::<f32>().ok()?.to_radians();
+    let color_index: f32 = color_index.parse().ok()?;
+
+    Some(Star::new(
+        equatorial_to_scene(right_ascension, declination),
+        magnitude.parse().ok()?,
+        Color::from_temperature(color_index_temperature(color_index)),
+    ))
+}
+
+/// Dirección unitaria de unas coordenadas ecuatoriales, girada al plano de la eclíptica,
+/// que en la escena es el plano xy de las órbitas sin inclinación
+fn equatorial_to_scene(right_ascension: f32, declination: f32) -> Vec3 {
+    let equatorial = Vec3::new(
+        declination.cos() * right_ascension.cos(),
+        declination.cos() * right_ascension.sin(),
+        declination.sin(),
+    );
+    let (sin, cos) = OBLIQUITY.sin_cos();
+    Vec3::new(
+        equatorial.x,
+        equatorial.y * cos + equatorial.z * sin,
+        -equatorial.y * sin + equatorial.z * cos,
+    )
+}
+
+/// Temperatura efectiva a partir del índice de color B-V (fórmula de Ballesteros, 2012)
+fn color_index_temperature(color_index: f32) -> f32 {
+    4600.0 * (1.0 / (0.92 * color_index + 1.7) + 1.0 / (0.92 * color_index + 0.62))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_star_reads_position_magnitude_and_color() {
+        let star = parse_star("Vernal, 0.0, 0.0, 1.5, 0.65").unwrap();
+        assert!((star.direction - Vec3::new(1.0, 0.0, 0.0)).magnitude() < 1e-5);
+        assert_eq!(star.magnitude, 1.5);
+        assert_eq!(star.color, Color::from_temperature(color_index_temperature(0.65)));
+
+        // El polo norte celeste queda inclinado la oblicuidad respecto al eje de la eclíptica
+        let pole = parse_star("Pole,0,90,2.0,0.0").unwrap();
+        assert!((pole.direction - Vec3::new(0.0, OBLIQUITY.sin(), OBLIQUITY.cos())).magnitude() < 1e-5);
+    }
+
+    #[test]
+    fn parse_star_rejects_malformed_lines() {
+        assert!(parse_star("Sirius,6.75,-16.7,-1.46").is_none());
+        assert!(parse_star("Sirius,6.75,-16.7,-1.46,0.0,extra").is_none());
+        assert!(parse_star("Sirius,six,-16.7,-1.46,0.0").is_none());
+        assert!(parse_star("Sirius,6.75,-16.7,bright,0.0").is_none());
+    }
+
+    #[test]
+    fn color_index_temperature_matches_the_sun() {
+        // B-V del Sol: unos 5780 K
+        assert!((color_index_temperature(0.65) - 5778.0).abs() < 10.0);
+        assert!(color_index_temperature(-0.3) > color_index_temperature(1.5));
+    }
+
+    #[test]
+    fn load_catalog_skips_the_header_and_comments() {
+        let path = std::env::temp_dir().join(format!("catalog-test-{}.csv", std::process::id()));
+        std::fs::write(&path, "# Estrellas brillantes\nname,ra_hours,dec_degrees,magnitude,color_index\n\nSirius,6.75,-16.7,-1.46,0.0\n# Vega\nVega,18.62,38.78,0.03,0.0\n").unwrap();
+        let stars = load_catalog(&path);
+
+        std::fs::write(&path, "name,ra_hours,dec_degrees,magnitude,color_index\nSirius,6.75,-16.7,-1.46,0.0\nVega,18.62\n").unwrap();
+        let error = load_catalog(&path).err().unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let stars = stars.unwrap();
+        assert_eq!(stars.len(), 2);
+        assert_eq!(stars[0].magnitude, -1.46);
+        assert_eq!(stars[1].magnitude, 0.03);
+        assert!(error.ends_with(":3: Vega,18.62"), "{}", error);
+    }
+
+    #[test]
+    fn load_catalog_keeps_the_first_star_without_a_header() {
+        let path = std::env::temp_dir().join(format!("catalog-headerless-{}.csv", std::process::id()));
+        std::fs::write(&path, "# Sin cabecera\nSirius,6.75,-16.7,-1.46,0.0\nVega,18.62,38.78,0.03,0.0\n").unwrap();
+        let stars = load_catalog(&path);
+
+        std::fs::write(&path, "star,ra,dec,mag,bv\nSirius,6.75,-16.7,-1.46,0.0\n").unwrap();
+        let error = load_catalog(&path).err().unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let stars = stars.unwrap();
+        assert_eq!(stars.len(), 2);
+        assert_eq!(stars[0].magnitude, -1.46);
+        // Otra cabecera no se confunde con la esperada: se informa como línea inválida
+        assert!(error.ends_with(":1: star,ra,dec,mag,bv"), "{}", error);
+    }
+
+    #[test]
+    fn bundled_catalog_parses() {
+        let stars = load_catalog(Path::new(&crate::asset_path("assets/catalogs/bright_stars.csv"))).unwrap();
+        assert!(!stars.is_empty());
+        assert!(stars.iter().all(|star| (star.direction.magnitude() - 1.0).abs() < 1e-5));
+    }
+}
@@ -0,0 +1,101 @@
+// src/clouds.rs
+
+use fastnoise_lite::FastNoiseLite;
+use nalgebra_glm::{Vec3, Vec4, Mat4, dot};
+use crate::color::Color;
+use crate::lighting::{PointLight, smoothstep};
+
+/// Capa de nubes de un planeta: una esfera algo mayor que gira a su propio ritmo.
+/// Las distancias están en radios del planeta.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CloudLayer {
+    pub color: Color,
+    pub altitude: f32,        // Altura de la capa sobre la superficie (fracción del radio)
+    pub scale: f32,           // Escala del ruido 3D de las nubes
+    pub threshold: f32,       // Umbral de ruido a partir del cual hay nube
+    pub softness: f32,        // Ancho del borde difuso de cada nube
+    pub opacity: f32,         // Opacidad máxima de una nube densa
+    pub rotation_period: f32, // Periodo de giro de la capa (0 = quieta)
+    pub evolve_speed: f32,    // Velocidad con la que cambian las formas de las nubes
+}
+
+impl CloudLayer {
+    /// Nubes blancas tipo Tierra
+    pub fn earth_like() -> Self {
+        CloudLayer {
+            color: Color::new(255, 255, 255),
+            altitude: 0.04,
+            scale: 7.0,
+            threshold: 0.1,
+            softness: 0.3,
+            opacity: 0.9,
+            rotation_period: 9.5,
+            evolve_speed: 0.1,
+        }
+    }
+
+    /// Nubes verdosas del mundo oceánico
+    pub fn greenish() -> Self {
+        CloudLayer {
+            color: Color::new(139, 232, 190),
+            rotation_period: 11.0,
+            ..CloudLayer::earth_like()
+        }
+    }
+
+    /// Tormentas de polvo finas y dispersas
+    pub fn dust_storms() -> Self {
+        CloudLayer {
+            color: Color::new(255, 193, 122),
+            threshold: 0.3,
+            opacity: 0.6,
+            rotation_period: 6.0,
+            ..CloudLayer::earth_like()
+        }
+    }
+
+    /// Densidad de nube en un punto del espacio del modelo de la capa (0 = despejado)
+    pub fn density(&self, noise: &FastNoiseLite, local_position: &Vec3, time: f32) -> f32 {
+        let drift = time * self.evolve_speed;
+        let value = noise.get_noise_3d(
+            local_position.x * self.scale + drift,
+            local_position.y * self.scale,
+            local_position.z * self.scale - drift,
+        );
+        smoothstep(self.threshold, self.threshold + self.softness, value) * self.opacity
+    }
+}
+
+/// Capa de nubes colocada alrededor de un planeta concreto en el espacio del mundo
+#[derive(Clone, Copy, Debug)]
+pub struct CloudShell {
+    pub layer: CloudLayer,
+    pub center: Vec3,
+    pub radius: f32,
+    pub world_to_local: Mat4, // Inversa de la matriz de modelo de la capa
+    pub time: f32,
+}
+
+impl CloudShell {
+    /// Fracción de luz que atraviesa las nubes desde `point` hacia la estrella
+    pub fn transmittance(&self, noise: &FastNoiseLite, point: &Vec3, light: &PointLight) -> f32 {
+        let light_dir = (light.position - point).normalize();
+        let origin = point - self.center;
+
+        // Salida del rayo por la capa; los puntos sobre la propia capa no se sombrean
+        let b = dot(&origin, &light_dir);
+        let c = origin.magnitude_squared() - self.radius * self.radius;
+        let discriminant = b * b - c;
+        if discriminant < 0.0 {
+            return 1.0;
+        }
+        let t = -b + discriminant.sqrt();
+        if t <= self.radius * 0.01 {
+            return 1.0;
+        }
+
+        let hit = point + light_dir * t;
+        let local = self.world_to_local * Vec4::new(hit.x, hit.y, hit.z, 1.0);
+        1.0 - self.layer.density(noise, &local.xyz(), self.time)
+    }
+}
@@ -1,6 +1,7 @@
 use std::fmt;
 use std::ops::Add;
 use std::ops::Mul;
+use std::sync::OnceLock;
 
 #[derive(Debug, Clone, Copy,PartialEq)]
 pub struct Color {
@@ -21,6 +22,33 @@ impl Color {
         Color { r, g, b }
     }
 
+    /// Crea un color a partir de componentes en [0, 1] (se recortan fuera de rango)
+    pub fn from_float(r: f32, g: f32, b: f32) -> Self {
+        Color {
+            r: (r.clamp(0.0, 1.0) * 255.0).round() as u8,
+            g: (g.clamp(0.0, 1.0) * 255.0).round() as u8,
+            b: (b.clamp(0.0, 1.0) * 255.0).round() as u8,
+        }
+    }
+
+    /// Color de un cuerpo negro a la temperatura dada (en kelvin), normalizado para que su
+    /// componente mayor valga 255. Se interpola en una tabla precalculada con `blackbody_srgb`.
+    pub fn from_temperature(kelvin: f32) -> Self {
+        static TABLE: OnceLock<Vec<[f32; 3]>> = OnceLock::new();
+        let table = TABLE.get_or_init(|| {
+            (0..BLACKBODY_TABLE_SIZE)
+                .map(|i| blackbody_srgb(BLACKBODY_MIN + i as f32 * BLACKBODY_STEP))
+                .collect()
+        });
+
+        let position = (kelvin.clamp(BLACKBODY_MIN, BLACKBODY_MAX) - BLACKBODY_MIN) / BLACKBODY_STEP;
+        let index = (position as usize).min(BLACKBODY_TABLE_SIZE - 2);
+        let t = position - index as f32;
+        let [r0, g0, b0] = table[index];
+        let [r1, g1, b1] = table[index + 1];
+        Color::from_float(r0 + (r1 - r0) * t, g0 + (g1 - g0) * t, b0 + (b1 - b0) * t)
+    }
+
     pub const fn black() -> Self {
         Color { r: 0, g: 0, b: 0 }
     }
@@ -43,10 +71,6 @@ impl Color {
         self.r == 0 && self.g == 0 && self.b == 0 
     }
 
-    pub fn blend_normal(&self, blend: &Color) -> Color {
-        if blend.is_black() { *self } else { *blend }
-    }
-    
     pub fn blend_multiply(&self, blend: &Color) -> Color {
         Color::new(
             ((self.r as f32 * blend.r as f32) / 255.0) as u8,
@@ -55,14 +79,6 @@ impl Color {
         )
     }
     
-    pub fn blend_add(&self, blend: &Color) -> Color {
-        Color::new(
-            (self.r as u16 + blend.r as u16).min(255) as u8,
-            (self.g as u16 + blend.g as u16).min(255) as u8,
-            (self.b as u16 + blend.b as u16).min(255) as u8
-        )
-    }
-    
     pub fn blend_subtract(&self, blend: &Color) -> Color {
         let r = (self.r as i16 - blend.r as i16).max(0).min(255) as u8;
         let g = (self.g as i16 - blend.g as i16).max(0).min(255) as u8;
@@ -102,3 +118,107 @@ impl fmt::Display for Color {
         write!(f, "Color(r: {}, g: {}, b: {})", self.r, self.g, self.b)
     }
 }
+
+/// Rango y resolución de la tabla de `Color::from_temperature`
+const BLACKBODY_MIN: f32 = 1000.0;
+const BLACKBODY_MAX: f32 = 40000.0;
+const BLACKBODY_STEP: f32 = 100.0;
+const BLACKBODY_TABLE_SIZE: usize = ((BLACKBODY_MAX - BLACKBODY_MIN) / BLACKBODY_STEP) as usize + 1;
+
+/// Color sRGB (componentes en [0, 1], ya con la curva gamma) de un cuerpo negro: integra la
+/// ley de Planck con las funciones de igualación CIE 1931, pasa de XYZ a sRGB lineal y normaliza
+/// para que la componente mayor valga 1. Los colores fuera de la gama sRGB se recortan.
+fn blackbody_srgb(kelvin: f32) -> [f32; 3] {
+    let mut xyz = [0.0f64; 3];
+    // Espectro visible de 380 a 780 nm en pasos de 5 nm
+    for step in 0..=80 {
+        let wavelength = 380.0 + step as f64 * 5.0;
+        let radiance = planck(wavelength, kelvin as f64);
+        let [x, y, z] = cie_1931(wavelength);
+        xyz[0] += x * radiance;
+        xyz[1] += y * radiance;
+        xyz[2] += z * radiance;
+    }
+
+    // XYZ a sRGB lineal (primarios de Rec. 709, blanco D65)
+    let [x, y, z] = xyz;
+    let linear = [
+        3.2406 * x - 1.5372 * y - 0.4986 * z,
+        -0.9689 * x + 1.8758 * y + 0.0415 * z,
+        0.0557 * x - 0.2040 * y + 1.0570 * z,
+    ];
+    let max = linear.iter().cloned().fold(f64::MIN, f64::max);
+    linear.map(|channel| srgb_encode((channel / max).max(0.0)) as f32)
+}
+
+/// Radiancia espectral de Planck (sin constantes de escala, solo importa la forma del espectro)
+fn planck(wavelength_nm: f64, kelvin: f64) -> f64 {
+    // Segunda constante de radiación, c2 = hc/k, en nm·K
+    const C2: f64 = 1.438_776_9e7;
+    let wavelength = wavelength_nm * 1e-3; // En micrómetros para mantener los números razonables
+    1.0 / (wavelength.powi(5) * ((C2 / (wavelength_nm * kelvin)).exp() - 1.0))
+}
+
+/// Funciones de igualación de color CIE 1931 (observador de 2°) según el ajuste analítico
+/// de varios lóbulos gaussianos de Wyman, Sloan y Shirley (2013)
+fn cie_1931(wavelength: f64) -> [f64; 3] {
+    let lobe = |center: f64, below: f64, above: f64| {
+        let t = (wavelength - center) * if wavelength < center { below } else { above };
+        (-0.5 * t * t).exp()
+    };
+    let x = 1.056 * lobe(599.8, 0.0264, 0.0323) + 0.362 * lobe(442.0, 0.0624, 0.0374) - 0.065 * lobe(501.1, 0.0490, 0.0382);
+    let y = 0.821 * lobe(568.8, 0.0213, 0.0247) + 0.286 * lobe(530.9, 0.0613, 0.0322);
+    let z = 1.217 * lobe(437.0, 0.0845, 0.0278) + 0.681 * lobe(459.0, 0.0385, 0.0725);
+    [x, y, z]
+}
+
+/// Curva de transferencia sRGB de lineal a codificado
+fn srgb_encode(linear: f64) -> f64 {
+    if linear <= 0.003_130_8 {
+        12.92 * linear
+    } else {
+        1.055 * linear.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blackbody_is_normalized_to_its_brightest_channel() {
+        for kelvin in [1500.0, 3000.0, 5800.0, 10000.0, 30000.0] {
+            let rgb = blackbody_srgb(kelvin);
+            let max = rgb.iter().cloned().fold(0.0, f32::max);
+            assert!((max - 1.0).abs() < 1e-5, "max {} at {} K", max, kelvin);
+            assert!(rgb.iter().all(|channel| (0.0..=1.0).contains(channel)), "{:?} at {} K", rgb, kelvin);
+        }
+    }
+
+    #[test]
+    fn blackbody_goes_from_red_to_blue() {
+        let cool = Color::from_temperature(3000.0);
+        assert_eq!(cool.r, 255);
+        assert!(cool.r > cool.g && cool.g > cool.b, "{}", cool);
+
+        // Cerca del blanco D65 los tres canales casi coinciden
+        let white = Color::from_temperature(6500.0);
+        assert!(white.r.min(white.g).min(white.b) > 230, "{}", white);
+
+        let hot = Color::from_temperature(20000.0);
+        assert_eq!(hot.b, 255);
+        assert!(hot.r < hot.g && hot.g < hot.b, "{}", hot);
+    }
+
+    #[test]
+    fn from_temperature_interpolates_the_table_and_clamps() {
+        // Entre dos entradas de la tabla cae entre sus colores
+        let [r0, ..] = blackbody_srgb(4000.0);
+        let [r1, ..] = blackbody_srgb(4000.0 + BLACKBODY_STEP);
+        let middle = Color::from_temperature(4000.0 + BLACKBODY_STEP * 0.5);
+        assert_eq!(middle.r, Color::from_float((r0 + r1) * 0.5, 0.0, 0.0).r);
+
+        assert_eq!(Color::from_temperature(BLACKBODY_MIN - 500.0), Color::from_temperature(BLACKBODY_MIN));
+        assert_eq!(Color::from_temperature(BLACKBODY_MAX + 5000.0), Color::from_temperature(BLACKBODY_MAX));
+    }
+}
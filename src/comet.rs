@@ -0,0 +1,112 @@
+// src/comet.rs
+
+use nalgebra_glm::Vec3;
+use crate::billboard::Billboard;
+use crate::color::Color;
+use crate::particles::{Curve, Emitter, Force, Gradient};
+
+/// Coma y colas de un cometa. Los valores son los del perihelio; lejos del sol el
+/// cometa emite menos y las colas se acortan y apagan (ver `activity`).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CometTails {
+    pub coma_color: Color,
+    pub coma_radius: f32,
+    pub ion_color: Color,
+    pub ion_speed: f32,     // Rapidez del viento solar que arrastra los iones
+    pub ion_lifetime: f32,  // Con la rapidez, fija el largo de la cola de iones
+    pub ion_width: f32,
+    pub dust_color: Color,
+    pub dust_pressure: f32, // Presión de radiación sobre el polvo (aceleración a distancia 1 del sol)
+    pub dust_lifetime: f32, // Más vida = cola de polvo más larga y curvada
+    pub dust_width: f32,
+    pub ion_rate: f32,      // Partículas por segundo de la cola de iones
+    pub dust_rate: f32,     // Partículas por segundo de la cola de polvo
+}
+
+impl CometTails {
+    /// Coma verdosa, cola de iones azul y cola de polvo amarillenta
+    pub fn halley_like() -> Self {
+        CometTails {
+            coma_color: Color::new(190, 235, 215),
+            coma_radius: 0.5,
+            ion_color: Color::new(110, 160, 255),
+            ion_speed: 3.0,
+            ion_lifetime: 1.4,
+            ion_width: 0.25,
+            dust_color: Color::new(255, 235, 190),
+            dust_pressure: 14.0,
+            dust_lifetime: 4.0,
+            dust_width: 0.5,
+            ion_rate: 150.0,
+            dust_rate: 60.0,
+        }
+    }
+
+    /// Emisor de la cola de iones: sale recta en dirección opuesta al sol
+    pub fn ion_emitter(&self) -> Emitter {
+        Emitter::new(
+            Vec3::new(1.0, 0.0, 0.0),
+            Curve::new(self.ion_speed * 0.8, self.ion_speed),
+            self.ion_rate,
+            Curve::new(self.ion_lifetime * 0.8, self.ion_lifetime),
+            Curve::new(self.ion_width * 0.3, self.ion_width * 1.3),
+            Gradient::new(self.ion_color, self.ion_color * 0.6),
+            Curve::new(0.25, 0.0),
+        )
+        .with_spread(0.02)
+    }
+
+    /// Emisor de la cola de polvo: los granos salen despacio, conservan la velocidad
+    /// orbital del núcleo y la presión de radiación los aleja del sol. Como el cometa
+    /// sigue avanzando, la cola se curva quedándose atrás en la órbita.
+    pub fn dust_emitter(&self) -> Emitter {
+        Emitter::new(
+            Vec3::new(1.0, 0.0, 0.0),
+            Curve::new(0.05, 0.2),
+            self.dust_rate,
+            Curve::new(self.dust_lifetime * 0.7, self.dust_lifetime),
+            Curve::new(self.dust_width * 0.3, self.dust_width * 1.3),
+            Gradient::new(self.dust_color, Color::new(200, 150, 110)),
+            Curve::new(0.12, 0.0),
+        )
+        .with_spread(0.8)
+        .with_inherited_velocity(1.0)
+        .with_force(Force::Radial { center: Vec3::zeros(), strength: self.dust_pressure })
+    }
+
+    /// Coloca el emisor de iones sobre el núcleo y lo ajusta a la actividad actual
+    pub fn steer_ion(&self, ion: &mut Emitter, nucleus: Vec3, sun: Vec3, activity: f32) {
+        ion.position = nucleus;
+        ion.direction = (nucleus - sun).normalize();
+        ion.rate = self.ion_rate * activity;
+        ion.speed = Curve::new(self.ion_speed * 0.8 * activity, self.ion_speed * activity);
+    }
+
+    /// Coloca el emisor de polvo sobre el núcleo, con su velocidad orbital actual
+    pub fn steer_dust(&self, dust: &mut Emitter, nucleus: Vec3, velocity: Vec3, sun: Vec3, activity: f32) {
+        dust.position = nucleus;
+        dust.velocity = velocity;
+        dust.direction = (nucleus - sun).normalize();
+        dust.rate = self.dust_rate * activity;
+        dust.forces = vec![Force::Radial { center: sun, strength: self.dust_pressure }];
+    }
+
+    /// Billboards de la coma: un halo amplio y un núcleo brillante
+    pub fn coma(&self, nucleus: Vec3, activity: f32) -> Vec<Billboard> {
+        if activity <= 0.0 {
+            return Vec::new();
+        }
+        let radius = self.coma_radius * activity.sqrt();
+        vec![
+            Billboard::new(nucleus, radius, self.coma_color, 0.6 * activity),
+            Billboard::new(nucleus, radius * 0.3, self.coma_color, activity),
+        ]
+    }
+}
+
+/// Actividad del cometa (0 a 1) según su distancia al sol: la sublimación crece con la
+/// irradiancia, así que la coma y las colas se alargan y brillan más cerca del perihelio
+pub fn activity(from_sun: Vec3, perihelion: f32) -> f32 {
+    let distance = from_sun.magnitude().max(1e-4);
+    (perihelion / distance).powi(2).min(1.0)
+}
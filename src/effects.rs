@@ -0,0 +1,117 @@
+// src/effects.rs
+
+use nalgebra_glm::{Mat4, Vec3};
+use crate::framebuffer::Framebuffer;
+use crate::orbital::BodyManager;
+use crate::particles::{EmitterId, ParticleSystem};
+
+/// Semilla fija de las partículas para que los cuadros sean reproducibles
+const PARTICLE_SEED: u64 = 1234;
+/// Segundos simulados antes del primer cuadro, para que las colas ya estén formadas
+const WARM_UP: f32 = 5.0;
+/// Paso de la simulación durante el calentamiento
+const WARM_UP_STEP: f32 = 0.016;
+/// Paso de tiempo para estimar la velocidad orbital por diferencias centrales
+const VELOCITY_STEP: f32 = 0.01;
+
+/// Emisores de las colas de un cometa
+struct CometEmitters {
+    body: usize, // Índice en `BodyManager::all_bodies`
+    ion: EmitterId,
+    dust: EmitterId,
+}
+
+/// Emisores de protuberancias y fulguraciones de una estrella
+struct StarEmitters {
+    body: usize,
+    prominences: EmitterId,
+    flare: EmitterId,
+}
+
+/// Efectos de partículas de la escena: se simulan cuadro a cuadro siguiendo a los cuerpos
+pub struct SceneEffects {
+    particles: ParticleSystem,
+    comets: Vec<CometEmitters>,
+    stars: Vec<StarEmitters>,
+}
+
+impl SceneEffects {
+    /// Crea los emisores de todos los cuerpos y simula los segundos previos a `start_time`
+    /// con la cámara en `eye`
+    pub fn new(body_manager: &BodyManager, eye: Vec3, start_time: f32) -> Self {
+        let mut particles = ParticleSystem::new(PARTICLE_SEED);
+        let comets = body_manager
+            .all_bodies
+            .iter()
+            .enumerate()
+            .filter_map(|(index, body)| {
+                body.comet_tails.map(|tails| CometEmitters {
+                    body: index,
+                    ion: particles.add_emitter(tails.ion_emitter()),
+                    dust: particles.add_emitter(tails.dust_emitter()),
+                })
+            })
+            .collect();
+        let stars = body_manager
+            .all_bodies
+            .iter()
+            .enumerate()
+            .filter_map(|(index, body)| {
+                body.solar.as_ref().map(|solar| {
+                    let sphere = body.sphere_occluder(body_manager.get_body_position(body, start_time), start_time);
+                    StarEmitters {
+                        body: index,
+                        prominences: particles.add_emitter(solar.prominence_emitter(sphere.center, sphere.radius)),
+                        flare: particles.add_emitter(solar.flare_emitter(sphere.center, sphere.radius)),
+                    }
+                })
+            })
+            .collect();
+
+        let mut effects = SceneEffects { particles, comets, stars };
+        let mut time = start_time - WARM_UP;
+        while time < start_time {
+            effects.update(body_manager, eye, time, WARM_UP_STEP);
+            time += WARM_UP_STEP;
+        }
+        effects
+    }
+
+    /// Mueve los emisores a la posición de sus cuerpos en `time` y avanza `dt` segundos.
+    /// Las protuberancias y fulguraciones nacen en el limbo visto desde `eye`.
+    pub fn update(&mut self, body_manager: &BodyManager, eye: Vec3, time: f32, dt: f32) {
+        let sun = body_manager.star_light(time).position;
+
+        for comet in &self.comets {
+            let body = &body_manager.all_bodies[comet.body];
+            let Some(tails) = &body.comet_tails else {
+                continue;
+            };
+            let position = body_manager.get_body_position(body, time);
+            let nucleus = body.sphere_occluder(position, time).center;
+            let velocity = (body_manager.get_body_position(body, time + VELOCITY_STEP)
+                - body_manager.get_body_position(body, time - VELOCITY_STEP))
+                / (2.0 * VELOCITY_STEP);
+            let activity = body.comet_activity(position, sun);
+
+            tails.steer_ion(self.particles.emitter_mut(comet.ion), nucleus, sun, activity);
+            tails.steer_dust(self.particles.emitter_mut(comet.dust), nucleus, velocity, sun, activity);
+        }
+
+        for star in &self.stars {
+            let body = &body_manager.all_bodies[star.body];
+            let Some(solar) = &body.solar else {
+                continue;
+            };
+            let center = body.sphere_occluder(body_manager.get_body_position(body, time), time).center;
+            solar.steer_prominences(self.particles.emitter_mut(star.prominences), center, eye);
+            solar.steer_flare(self.particles.emitter_mut(star.flare), center, eye, time);
+        }
+
+        self.particles.update(dt);
+    }
+
+    pub fn render(&self, framebuffer: &mut Framebuffer, view_matrix: &Mat4, projection_matrix: &Mat4, viewport_matrix: &Mat4) {
+        self.particles.render(framebuffer, view_matrix, projection_matrix, viewport_matrix);
+    }
+}
@@ -6,25 +6,78 @@ pub enum CelestialType {
     Star,
     Planet,
     GasGiant,
-    Ringed,
     Rings,
-    Planet2,
-    Mars,
     Moon,
     Comet,
 
     // Para efectos atmosféricos
+    Atmosphere,
+    Clouds,
     // Agrega otros tipos según sea necesario
 }
 
+impl CelestialType {
+    /// Cómo se combinan los fragmentos de este tipo con el framebuffer
+    pub fn blend_mode(&self) -> BlendMode {
+        match self {
+            CelestialType::Atmosphere => BlendMode::Additive,
+            CelestialType::Clouds | CelestialType::Rings => BlendMode::Alpha,
+            _ => BlendMode::Opaque,
+        }
+    }
+}
+
+/// Modo de mezcla de un fragmento con el framebuffer
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum BlendMode {
+    Opaque,   // Reemplaza el color y escribe profundidad
+    Additive, // Suma el color sin escribir profundidad (brillos, atmósferas)
+    Alpha,    // Mezcla con la opacidad del fragmento sin escribir profundidad (nubes, anillos)
+    Emissive, // Suma el color al buffer de emisión sin escribir profundidad (destellos, protuberancias)
+}
+
+/// Resultado del shader de fragmentos
+#[derive(Clone, Copy, Debug)]
+pub struct FragmentColor {
+    pub color: Color,
+    pub emission: Color, // Se suma en el post-proceso (estrellas, luces nocturnas)
+    pub alpha: f32,      // Opacidad, solo se usa con `BlendMode::Alpha`
+}
+
+impl FragmentColor {
+    pub fn opaque(color: Color) -> Self {
+        FragmentColor {
+            color,
+            emission: Color::black(),
+            alpha: 1.0,
+        }
+    }
+
+    /// Fragmento que no aporta nada (con mezcla alfa)
+    pub fn transparent() -> Self {
+        FragmentColor::opaque(Color::black()).with_alpha(0.0)
+    }
+
+    pub fn with_emission(mut self, emission: Color) -> Self {
+        self.emission = emission;
+        self
+    }
+
+    pub fn with_alpha(mut self, alpha: f32) -> Self {
+        self.alpha = alpha;
+        self
+    }
+}
+
 
 pub struct Fragment {
     pub position: Vec2,
-    pub color: Color,
     pub depth: f32,
     pub normal: Vec3,
-    pub intensity: f32,
     pub vertex_position: Vec4, // Cambiado a Vec4
+    pub world_position: Vec3,  // Posición en el espacio del mundo, para la iluminación
+    pub tex_coords: Vec2,
+    pub uv_footprint: f32,     // Área en UV que cubre un píxel, para elegir el nivel de mipmap
     pub celestial_type: CelestialType,
 }
 
@@ -32,21 +85,32 @@ impl Fragment {
     pub fn new(
         x: f32,
         y: f32,
-        color: Color,
         depth: f32,
         normal: Vec3,
-        intensity: f32,
         vertex_position: Vec4, // Ahora es Vec4
         celestial_type: CelestialType,
     ) -> Self {
         Fragment {
             position: Vec2::new(x, y),
-            color,
             depth,
             normal,
-            intensity,
             vertex_position,
+            world_position: Vec3::zeros(),
+            tex_coords: Vec2::zeros(),
+            uv_footprint: 0.0,
             celestial_type,
         }
     }
+
+    pub fn with_world_position(mut self, world_position: Vec3) -> Self {
+        self.world_position = world_position;
+        self
+    }
+
+    /// Coordenadas de textura interpoladas y el área en UV que cubre el píxel
+    pub fn with_tex_coords(mut self, tex_coords: Vec2, uv_footprint: f32) -> Self {
+        self.tex_coords = tex_coords;
+        self.uv_footprint = uv_footprint;
+        self
+    }
 }
@@ -1,11 +1,29 @@
+use std::path::Path;
+use image::{ImageResult, RgbImage};
 use crate::color::Color;
 
+/// Cómo se resuelven los fragmentos semitransparentes (`blend_point`)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TransparencyMode {
+    /// Mezcla directa en el orden de dibujo: los objetos deben llegar de atrás hacia delante
+    #[default]
+    Sorted,
+    /// Weighted blended OIT (McGuire y Bavoil): se acumula por píxel y se resuelve con
+    /// `resolve_transparency` al cerrar el pase transparente, sin depender del orden de dibujo.
+    /// Lo que se mezcla después en el mismo cuadro (partículas alfa, ya ordenadas) va directo.
+    WeightedBlended,
+}
+
 pub struct Framebuffer {
     pub width: usize,
     pub height: usize,
     pub buffer: Vec<u32>,
     pub zbuffer: Vec<f32>,
     pub emissive_buffer: Vec<u32>, // Nuevo buffer para emisivos
+    transparency: TransparencyMode,
+    accum_buffer: Vec<[f32; 4]>, // OIT: color ponderado (RGB) y suma de pesos (A)
+    revealage_buffer: Vec<f32>,  // OIT: fracción de fondo que sigue visible
+    transparency_resolved: bool, // OIT: ya se compuso el pase transparente de este cuadro
     background_color: u32,
     current_color: u32,
 }
@@ -18,6 +36,10 @@ impl Framebuffer {
             buffer: vec![0; width * height],
             zbuffer: vec![f32::INFINITY; width * height],
             emissive_buffer: vec![0; width * height], // Inicializar
+            transparency: TransparencyMode::default(),
+            accum_buffer: vec![[0.0; 4]; width * height],
+            revealage_buffer: vec![1.0; width * height],
+            transparency_resolved: false,
             background_color: 0x000000,
             current_color: 0x000000,
         }
@@ -33,6 +55,17 @@ impl Framebuffer {
         for emissive_pixel in self.emissive_buffer.iter_mut() {
             *emissive_pixel = 0x000000;
         }
+        self.clear_transparency();
+        self.transparency_resolved = false;
+    }
+
+    fn clear_transparency(&mut self) {
+        for accum in self.accum_buffer.iter_mut() {
+            *accum = [0.0; 4];
+        }
+        for revealage in self.revealage_buffer.iter_mut() {
+            *revealage = 1.0;
+        }
     }
     pub fn set_pixel(&mut self, x: i32, y: i32, color: Color, depth: f32) {
         if x >= 0 && x < self.width as i32 && y >= 0 && y < self.height as i32 {
@@ -43,20 +76,101 @@ impl Framebuffer {
             }
         }
     }
-    pub fn point(&mut self, x: usize, y: usize, depth: f32, emissive: bool) {
+    /// Escribe el color actual y su emisión; la emisión siempre se sobrescribe para que
+    /// un cuerpo opaco delante tape el brillo de lo que queda detrás
+    pub fn point(&mut self, x: usize, y: usize, depth: f32, emission: u32) {
         if x < self.width && y < self.height {
             let index = y * self.width + x;
 
             if self.zbuffer[index] > depth {
                 self.buffer[index] = self.current_color;
-                if emissive {
-                    self.emissive_buffer[index] = self.current_color;
-                }
+                self.emissive_buffer[index] = emission;
                 self.zbuffer[index] = depth;
             }
         }
     }
 
+    /// Suma el color actual al píxel si pasa la prueba de profundidad, sin escribirla
+    pub fn add_point(&mut self, x: usize, y: usize, depth: f32) {
+        if x < self.width && y < self.height {
+            let index = y * self.width + x;
+
+            if self.zbuffer[index] > depth {
+                self.buffer[index] = blend_add(self.buffer[index], self.current_color);
+            }
+        }
+    }
+
+    /// Suma el color actual a la emisión del píxel si pasa la prueba de profundidad, sin escribirla
+    pub fn add_emission(&mut self, x: usize, y: usize, depth: f32) {
+        if x < self.width && y < self.height {
+            let index = y * self.width + x;
+
+            if self.zbuffer[index] > depth {
+                self.emissive_buffer[index] = blend_add(self.emissive_buffer[index], self.current_color);
+            }
+        }
+    }
+
+    /// Mezcla el color actual sobre el píxel con opacidad `alpha`, sin escribir profundidad.
+    /// También tapa en esa proporción la emisión de lo que queda detrás. Con OIT se acumula
+    /// hasta `resolve_transparency`; a partir de ahí se mezcla directamente, porque ya nada
+    /// compondría lo acumulado.
+    pub fn blend_point(&mut self, x: usize, y: usize, depth: f32, alpha: f32) {
+        if x < self.width && y < self.height && alpha > 0.0 {
+            let index = y * self.width + x;
+
+            if self.zbuffer[index] > depth {
+                let alpha = alpha.min(1.0);
+                match (self.transparency, self.transparency_resolved) {
+                    (TransparencyMode::WeightedBlended, false) => {
+                        let color = Color::from_hex(self.current_color);
+                        let weight = alpha * oit_weight(depth);
+                        let accum = &mut self.accum_buffer[index];
+                        accum[0] += color.r as f32 * weight;
+                        accum[1] += color.g as f32 * weight;
+                        accum[2] += color.b as f32 * weight;
+                        accum[3] += weight;
+                        self.revealage_buffer[index] *= 1.0 - alpha;
+                    }
+                    _ => {
+                        self.buffer[index] = blend_colors(self.buffer[index], self.current_color, alpha);
+                        self.emissive_buffer[index] = blend_colors(self.emissive_buffer[index], 0x000000, alpha);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Compone lo acumulado por la OIT sobre los buffers de color y emisión y lo reinicia;
+    /// el resto del cuadro mezcla directamente. En modo `Sorted` no hay nada acumulado y no hace nada.
+    pub fn resolve_transparency(&mut self) {
+        if self.transparency != TransparencyMode::WeightedBlended {
+            return;
+        }
+        self.transparency_resolved = true;
+        for index in 0..self.buffer.len() {
+            let coverage = 1.0 - self.revealage_buffer[index];
+            if coverage <= 0.0 {
+                continue;
+            }
+            let [r, g, b, weight] = self.accum_buffer[index];
+            let weight = weight.max(1e-5);
+            let average = Color::new(
+                (r / weight).min(255.0) as u8,
+                (g / weight).min(255.0) as u8,
+                (b / weight).min(255.0) as u8,
+            );
+            self.buffer[index] = blend_colors(self.buffer[index], average.to_hex(), coverage);
+            self.emissive_buffer[index] = blend_colors(self.emissive_buffer[index], 0x000000, coverage);
+        }
+        self.clear_transparency();
+    }
+
+    pub fn set_transparency_mode(&mut self, mode: TransparencyMode) {
+        self.transparency = mode;
+    }
+
     pub fn set_background_color(&mut self, color: u32) {
         self.background_color = color;
     }
@@ -64,6 +178,19 @@ impl Framebuffer {
     pub fn set_current_color(&mut self, color: u32) {
         self.current_color = color;
     }
+    /// Convierte el buffer de color a una imagen RGB
+    pub fn to_rgb_image(&self) -> RgbImage {
+        RgbImage::from_fn(self.width as u32, self.height as u32, |x, y| {
+            let color = Color::from_hex(self.buffer[y as usize * self.width + x as usize]);
+            image::Rgb([color.r, color.g, color.b])
+        })
+    }
+
+    /// Guarda el buffer de color como imagen (el formato se deduce de la extensión)
+    pub fn save_png<P: AsRef<Path>>(&self, path: P) -> ImageResult<()> {
+        self.to_rgb_image().save(path)
+    }
+
     /// Aplica un overlay oscuro con intensidad variable
     pub fn apply_overlay(&mut self, intensity: f32) {
         // Asegúrate de que la operación de overlay sea eficiente y no cree nuevas estructuras enormes
@@ -75,6 +202,13 @@ impl Framebuffer {
     }
 }
 
+/// Peso de un fragmento en la OIT según su profundidad en NDC: lo cercano domina
+/// (ecuación 10 de McGuire y Bavoil, 2013)
+fn oit_weight(depth: f32) -> f32 {
+    let z = (depth * 0.5 + 0.5).clamp(0.0, 1.0);
+    (3e3 * (1.0 - z).powi(3)).max(1e-2)
+}
+
 // Función para mezclar colores
 fn blend_colors(base: u32, overlay: u32, intensity: f32) -> u32 {
     // Extrae componentes RGB
@@ -106,8 +240,9 @@ pub fn post_process(framebuffer: &mut Framebuffer) {
 
 // Función para mezclar colores usando blend add
 pub fn blend_add(base: u32, emissive: u32) -> u32 {
-    let r = ((base >> 16) & 0xFF).saturating_add((emissive >> 16) & 0xFF);
-    let g = ((base >> 8) & 0xFF).saturating_add((emissive >> 8) & 0xFF);
-    let b = (base & 0xFF).saturating_add(emissive & 0xFF);
+    // Se satura cada canal en 255 para que no se desborde sobre el siguiente
+    let r = (((base >> 16) & 0xFF) + ((emissive >> 16) & 0xFF)).min(255);
+    let g = (((base >> 8) & 0xFF) + ((emissive >> 8) & 0xFF)).min(255);
+    let b = ((base & 0xFF) + (emissive & 0xFF)).min(255);
     (r << 16) | (g << 8) | b
 }
\ No newline at end of file
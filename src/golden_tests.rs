@@ -0,0 +1,395 @@
+// src/golden_tests.rs
+//
+// Pruebas de regresión por imagen: renderiza cada cuerpo celeste con cámara,
+// tiempo y semillas de ruido fijos y compara contra PNGs de referencia en
+// `tests/golden/`. Para regenerar las referencias tras un cambio intencional:
+//
+//     UPDATE_GOLDEN=1 cargo test golden
+
+use std::path::PathBuf;
+use std::sync::Arc;
+use image::{Rgba, RgbaImage, RgbImage};
+use nalgebra_glm::Vec3;
+use crate::framebuffer::{Framebuffer, TransparencyMode};
+use crate::renderer::render;
+use crate::effects::SceneEffects;
+use crate::fragment::BlendMode;
+use crate::particles::{Curve, Emitter, Gradient, ParticleSystem};
+use crate::color::Color;
+use crate::orbital::BodyManager;
+use crate::texture::{SurfaceTextures, Texture};
+use crate::bake::TerrainBake;
+use crate::uniforms::{SceneLighting, ViewTransform};
+use crate::{body_uniforms, create_body_manager, render_atmosphere, render_clouds, render_coma, render_rings, create_perspective_matrix, create_view_matrix, create_viewport_matrix};
+
+const WIDTH: usize = 128;
+const HEIGHT: usize = 128;
+const TIME: f32 = 3.0;
+
+/// Diferencia máxima por canal que se considera igual
+const CHANNEL_TOLERANCE: u8 = 8;
+/// Fracción máxima de píxeles fuera de tolerancia
+const MAX_MISMATCH_RATIO: f32 = 0.005;
+/// Entre la superficie horneada y la calculada por píxel: fracción de píxeles fuera de
+/// tolerancia y diferencia media por canal
+const BAKE_MAX_MISMATCH_RATIO: f32 = 0.05;
+const BAKE_MAX_MEAN_DIFFERENCE: f32 = 1.5;
+
+fn golden_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join("golden")
+}
+
+fn diff_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("target").join("golden-diff")
+}
+
+/// Desde dónde se mira el cuerpo respecto al sol
+#[derive(Clone, Copy)]
+enum Viewpoint {
+    Terminator, // De lado, para que se vean el día y el terminador
+    NightSide,  // Desde detrás del cuerpo, con el sol a la espalda de este
+    Overview,   // De lejos y de perfil, para que entren las colas de un cometa
+}
+
+/// Renderiza solo el cuerpo con ese nombre, en su posición orbital en `time`
+fn render_body(body_name: &str, time: f32, viewpoint: Viewpoint, transparency: TransparencyMode) -> RgbImage {
+    render_in(&create_body_manager(), body_name, time, viewpoint, transparency)
+}
+
+/// Como `render_body`, pero con una escena ya preparada (por ejemplo, con texturas propias)
+fn render_in(body_manager: &BodyManager, body_name: &str, time: f32, viewpoint: Viewpoint, transparency: TransparencyMode) -> RgbImage {
+    render_frame(body_manager, body_name, time, viewpoint, transparency, None)
+}
+
+/// Como `render_in`, dibujando además `particles` junto a los efectos de la escena
+fn render_frame(
+    body_manager: &BodyManager,
+    body_name: &str,
+    time: f32,
+    viewpoint: Viewpoint,
+    transparency: TransparencyMode,
+    particles: Option<&ParticleSystem>,
+) -> RgbImage {
+    let body = body_manager
+        .get_body_by_name(body_name)
+        .expect("No body with this name");
+
+    let position = body_manager.get_body_position(body, time);
+    let mesh = body_manager.mesh(body);
+    let vertex_array = mesh.vertices();
+    let mesh_radius = vertex_array
+        .iter()
+        .map(|vertex| vertex.position.magnitude())
+        .fold(0.0, f32::max);
+
+    let up = Vec3::new(0.0, 1.0, 0.0);
+    let sun_direction = if position.magnitude() > 0.0 {
+        (-position).normalize()
+    } else {
+        Vec3::new(0.0, 0.0, 1.0)
+    };
+    let side = sun_direction.cross(&up).normalize();
+    let view_direction = match viewpoint {
+        Viewpoint::Terminator => (sun_direction + side + up * 0.3).normalize(),
+        Viewpoint::NightSide => (-sun_direction + side * 0.6 + up * 0.3).normalize(),
+        Viewpoint::Overview => (side + up * 0.5).normalize(),
+    };
+    // Los anillos también tienen que entrar en el encuadre
+    let extent = mesh_radius * body.scale * body.rings.as_ref().map_or(1.0, |rings| rings.outer_radius.max(1.0));
+    let (target, extent) = match (viewpoint, body.comet_tails) {
+        // Se encuadra la cola entera, que se aleja del sol
+        (Viewpoint::Overview, Some(tails)) => {
+            let length = tails.ion_speed * tails.ion_lifetime;
+            (position - sun_direction * length * 0.5, length * 0.6)
+        }
+        _ => (position, extent),
+    };
+    let eye = target + view_direction * extent * 3.0;
+
+    let mut framebuffer = Framebuffer::new(WIDTH, HEIGHT);
+    framebuffer.set_transparency_mode(transparency);
+    framebuffer.clear();
+
+    let view_matrix = create_view_matrix(eye, target, up);
+    let projection_matrix = create_perspective_matrix(WIDTH as f32, HEIGHT as f32);
+    let viewport_matrix = create_viewport_matrix(WIDTH as f32, HEIGHT as f32);
+    let view = ViewTransform::new(view_matrix, projection_matrix, viewport_matrix);
+    let light = body_manager.star_light(time);
+    let mut uniforms = body_uniforms(
+        body,
+        position,
+        SceneLighting::new(light, eye, body_manager.shadow_casters(body, time)),
+        view,
+        time,
+    );
+
+    render(&mut framebuffer, &uniforms, vertex_array, body.shader_type);
+    render_clouds(&mut framebuffer, body, mesh, position, &mut uniforms);
+    render_rings(&mut framebuffer, body, body_manager.ring_mesh(body), position, &mut uniforms, body_manager.ring_shadow_casters(body, time));
+    framebuffer.resolve_transparency();
+    render_coma(&mut framebuffer, body, position, &uniforms);
+    render_atmosphere(&mut framebuffer, body, mesh, position, uniforms);
+    // Solo las partículas del propio cuerpo: las colas de otros podrían cruzar el encuadre
+    if body.comet_tails.is_some() || body.solar.is_some() {
+        SceneEffects::new(body_manager, eye, time).render(&mut framebuffer, &view_matrix, &projection_matrix, &viewport_matrix);
+    }
+    if let Some(particles) = particles {
+        particles.render(&mut framebuffer, &view_matrix, &projection_matrix, &viewport_matrix);
+    }
+    crate::framebuffer::post_process(&mut framebuffer);
+
+    framebuffer.to_rgb_image()
+}
+
+/// Imagen con la diferencia absoluta por canal, amplificada para que sea visible
+fn diff_image(expected: &RgbImage, actual: &RgbImage) -> RgbImage {
+    RgbImage::from_fn(expected.width(), expected.height(), |x, y| {
+        let e = expected.get_pixel(x, y);
+        let a = actual.get_pixel(x, y);
+        image::Rgb([0, 1, 2].map(|c| e[c].abs_diff(a[c]).saturating_mul(4)))
+    })
+}
+
+fn check_golden(body_name: &str, name: &str) {
+    check_golden_at(body_name, TIME, Viewpoint::Terminator, name);
+}
+
+fn check_golden_at(body_name: &str, time: f32, viewpoint: Viewpoint, name: &str) {
+    check_image(render_body(body_name, time, viewpoint, TransparencyMode::Sorted), name);
+}
+
+/// Fracción de píxeles con algún canal fuera de `CHANNEL_TOLERANCE`
+fn mismatch_ratio(expected: &RgbImage, actual: &RgbImage) -> f32 {
+    let mismatched = expected
+        .pixels()
+        .zip(actual.pixels())
+        .filter(|(e, a)| (0..3).any(|c| e[c].abs_diff(a[c]) > CHANNEL_TOLERANCE))
+        .count();
+    mismatched as f32 / (WIDTH * HEIGHT) as f32
+}
+
+fn check_image(actual: RgbImage, name: &str) {
+    let reference_path = golden_dir().join(format!("{}.png", name));
+
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::create_dir_all(golden_dir()).unwrap();
+        actual.save(&reference_path).unwrap();
+        return;
+    }
+
+    let expected = image::open(&reference_path)
+        .unwrap_or_else(|e| {
+            panic!(
+                "Missing reference {} ({}); run with UPDATE_GOLDEN=1 to create it",
+                reference_path.display(),
+                e
+            )
+        })
+        .to_rgb8();
+
+    assert_eq!(expected.dimensions(), actual.dimensions(), "Reference size mismatch for {}", name);
+
+    let ratio = mismatch_ratio(&expected, &actual);
+    if ratio > MAX_MISMATCH_RATIO {
+        std::fs::create_dir_all(diff_dir()).unwrap();
+        let actual_path = diff_dir().join(format!("{}_actual.png", name));
+        let diff_path = diff_dir().join(format!("{}_diff.png", name));
+        actual.save(&actual_path).unwrap();
+        diff_image(&expected, &actual).save(&diff_path).unwrap();
+        panic!(
+            "{}: {:.2}% of the pixels differ from the reference; see {} and {}",
+            name,
+            ratio * 100.0,
+            actual_path.display(),
+            diff_path.display()
+        );
+    }
+}
+
+#[test]
+fn golden_star() {
+    check_golden("Star", "star");
+}
+
+#[test]
+fn golden_planet() {
+    check_golden("Planet", "planet");
+}
+
+#[test]
+fn golden_gas_giant() {
+    check_golden("GasGiant", "gas_giant");
+}
+
+#[test]
+fn golden_ringed() {
+    check_golden("Ringed", "ringed");
+}
+
+/// Anillos a contraluz: solo se ve la luz que atraviesa las zonas finas y la sombra del planeta
+#[test]
+fn golden_rings() {
+    check_golden_at("Ringed", TIME, Viewpoint::NightSide, "rings");
+}
+
+/// Los anillos con OIT ponderada deben verse igual que con la mezcla ordenada salvo
+/// en los solapes, que aquí no dependen del orden de dibujo
+#[test]
+fn golden_ringed_oit() {
+    check_image(render_body("Ringed", TIME, Viewpoint::Terminator, TransparencyMode::WeightedBlended), "ringed_oit");
+}
+
+#[test]
+fn golden_planet2() {
+    check_golden("Planet2", "planet2");
+}
+
+#[test]
+fn golden_mars() {
+    check_golden("Mars", "mars");
+}
+
+#[test]
+fn golden_moon() {
+    check_golden("Moon", "moon");
+}
+
+#[test]
+fn golden_comet() {
+    check_golden("Comet", "comet");
+}
+
+/// Coma, cola de iones recta y cola de polvo curvada, cerca del perihelio
+#[test]
+fn golden_comet_tails() {
+    check_golden_at("Comet", TIME, Viewpoint::Overview, "comet_tails");
+}
+
+/// La luna pasa entre el planeta y el sol: su sombra debe verse sobre el planeta
+#[test]
+fn golden_planet_eclipse() {
+    check_golden_at("Planet", 4.7, Viewpoint::Terminator, "planet_eclipse");
+}
+
+/// El lado de noche del planeta muestra las luces de las ciudades
+#[test]
+fn golden_planet_night() {
+    check_golden_at("Planet", TIME, Viewpoint::NightSide, "planet_night");
+}
+
+/// Mapas sintéticos para el planeta: una retícula de latitud y longitud con el hemisferio
+/// norte más claro y el meridiano 0 en rojo, océano especular en el sur, relieve ondulado y
+/// puntos de luz nocturnos. Sirven para ver que las UV no están giradas ni reflejadas.
+fn synthetic_textures() -> SurfaceTextures {
+    let (width, height) = (128, 64);
+    let grid = |x: u32, y: u32| x.is_multiple_of(16) || y.is_multiple_of(16);
+
+    let albedo = RgbaImage::from_fn(width, height, |x, y| {
+        if x < 2 {
+            Rgba([220, 40, 30, 255])
+        } else if grid(x, y) {
+            Rgba([240, 240, 230, 255])
+        } else if y < height / 2 {
+            Rgba([170, 140, 90, 255])
+        } else {
+            Rgba([30, 60, 130, 255])
+        }
+    });
+    let specular = RgbaImage::from_fn(width, height, |_, y| {
+        let shine = if y < height / 2 { 0 } else { 153 };
+        Rgba([shine, shine, shine, 255])
+    });
+    let normal = RgbaImage::from_fn(width, height, |x, _| {
+        // Ondas norte-sur: la normal se inclina hacia el este y el oeste
+        let slope = (x as f32 / width as f32 * std::f32::consts::TAU * 8.0).sin() * 0.5;
+        let tangent = Vec3::new(slope, 0.0, 1.0).normalize();
+        let encode = |value: f32| ((value * 0.5 + 0.5) * 255.0) as u8;
+        Rgba([encode(tangent.x), encode(tangent.y), encode(tangent.z), 255])
+    });
+    let night = RgbaImage::from_fn(width, height, |x, y| {
+        let lit = x % 8 == 4 && y % 8 == 4 && y < height / 2;
+        if lit { Rgba([255, 200, 120, 255]) } else { Rgba([0, 0, 0, 255]) }
+    });
+
+    SurfaceTextures::new(1.0)
+        .with_albedo(Arc::new(Texture::from_image(&albedo)))
+        .with_specular(Arc::new(Texture::from_image(&specular)))
+        .with_normal(Arc::new(Texture::from_image(&normal)), 0.8)
+        .with_night(Arc::new(Texture::from_image(&night)))
+}
+
+/// El planeta con mapas de imagen en vez del material procedural
+#[test]
+fn golden_textured() {
+    let mut body_manager = create_body_manager();
+    for body in body_manager.all_bodies.iter_mut().filter(|body| body.name == "Planet") {
+        body.textures = Some(synthetic_textures());
+    }
+    check_image(render_in(&body_manager, "Planet", TIME, Viewpoint::Terminator, TransparencyMode::Sorted), "textured");
+}
+
+/// El planeta con su terreno horneado a texturas debe verse como con el ruido por píxel
+#[test]
+fn golden_baked() {
+    let procedural = render_body("Planet", TIME, Viewpoint::Terminator, TransparencyMode::Sorted);
+
+    let mut body_manager = create_body_manager();
+    for body in body_manager.all_bodies.iter_mut().filter(|body| body.name == "Planet") {
+        let mesh = body_manager.assets.mesh(body.detailed_mesh());
+        body.textures = Some(TerrainBake::new(body, mesh, 512).to_textures());
+    }
+    let baked = render_in(&body_manager, "Planet", TIME, Viewpoint::Terminator, TransparencyMode::Sorted);
+
+    // Los texels interpolados suavizan algo el borde de las costas: se admiten más píxeles
+    // distintos que en las referencias, pero la diferencia media tiene que ser casi nula
+    let ratio = mismatch_ratio(&procedural, &baked);
+    assert!(ratio <= BAKE_MAX_MISMATCH_RATIO, "{:.2}% of the baked pixels differ from the procedural surface", ratio * 100.0);
+    let mean = mean_difference(&procedural, &baked);
+    assert!(mean <= BAKE_MAX_MEAN_DIFFERENCE, "baked surface differs by {:.2} per channel on average", mean);
+    check_image(baked, "baked");
+}
+
+/// Diferencia media por canal entre dos imágenes, en [0, 255]
+fn mean_difference(expected: &RgbImage, actual: &RgbImage) -> f32 {
+    let total: u64 = expected
+        .pixels()
+        .zip(actual.pixels())
+        .map(|(e, a)| (0..3).map(|c| e[c].abs_diff(a[c]) as u64).sum::<u64>())
+        .sum();
+    total as f32 / (WIDTH * HEIGHT * 3) as f32
+}
+
+/// Humo con mezcla alfa delante del planeta: con OIT se dibuja después de resolver el pase
+/// transparente y tiene que verse igual que con la mezcla ordenada
+#[test]
+fn golden_alpha_particles_oit() {
+    let body_manager = create_body_manager();
+    let planet = body_manager.get_body_by_name("Planet").unwrap();
+    let sphere = planet.sphere_occluder(body_manager.get_body_position(planet, TIME), TIME);
+
+    let mut particles = ParticleSystem::new(7);
+    let mut smoke = Emitter::new(
+        Vec3::new(0.0, 1.0, 0.0),
+        Curve::new(0.1, 0.3),
+        120.0,
+        Curve::new(1.0, 2.0),
+        Curve::new(0.1, 0.3),
+        Gradient::new(Color::new(230, 220, 200), Color::new(90, 80, 70)),
+        Curve::new(0.8, 0.0),
+    )
+    .with_spread(std::f32::consts::PI)
+    .with_shell(sphere.radius * 1.1, 0.3)
+    .with_blend_mode(BlendMode::Alpha);
+    smoke.position = sphere.center;
+    particles.add_emitter(smoke);
+    for _ in 0..120 {
+        particles.update(0.016);
+    }
+
+    let oit = render_frame(&body_manager, "Planet", TIME, Viewpoint::Terminator, TransparencyMode::WeightedBlended, Some(&particles));
+    let sorted = render_frame(&body_manager, "Planet", TIME, Viewpoint::Terminator, TransparencyMode::Sorted, Some(&particles));
+    let without = render_in(&body_manager, "Planet", TIME, Viewpoint::Terminator, TransparencyMode::WeightedBlended);
+    assert!(mismatch_ratio(&without, &oit) > 0.05, "the smoke does not show up with OIT");
+    assert!(mismatch_ratio(&sorted, &oit) <= MAX_MISMATCH_RATIO, "OIT and sorted smoke differ");
+    check_image(oit, "alpha_particles_oit");
+}
@@ -0,0 +1,137 @@
+// src/headless.rs
+
+use std::path::PathBuf;
+use nalgebra_glm::Vec3;
+use crate::framebuffer::Framebuffer;
+use crate::camera::Camera;
+use crate::stars::StarField;
+use crate::effects::SceneEffects;
+use crate::options::{SceneOptions, parse_value};
+use crate::{bake_surfaces, create_body_manager, create_skybox, create_stars, render_scene};
+
+/// Semilla fija del campo de estrellas para que los cuadros sean reproducibles
+const STAR_FIELD_SEED: u64 = 42;
+
+/// Parámetros del renderizado sin ventana
+pub struct HeadlessConfig {
+    pub frames: usize,
+    pub width: usize,
+    pub height: usize,
+    pub output_dir: PathBuf,
+    pub start_time: f32,
+    pub time_step: f32,
+    pub follow: Option<usize>,
+    pub scene: SceneOptions,
+}
+
+impl Default for HeadlessConfig {
+    fn default() -> Self {
+        HeadlessConfig {
+            frames: 1,
+            width: 800,
+            height: 600,
+            output_dir: PathBuf::from("frames"),
+            start_time: 0.0,
+            time_step: 0.016, // Mismo paso que el bucle con ventana
+            follow: None,
+            scene: SceneOptions::default(),
+        }
+    }
+}
+
+impl HeadlessConfig {
+    /// Interpreta los argumentos de línea de comandos, por ejemplo:
+    /// `--headless --frames 120 --size 1920x1080 --output renders --follow 2 --oit --star-catalog estrellas.csv --bake-resolution 2048 --bake-cache /tmp/bakes`
+    pub fn from_args(args: &[String]) -> Result<Self, String> {
+        let mut config = HeadlessConfig::default();
+        let mut iter = args.iter();
+
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "--headless" => {}
+                "--frames" => config.frames = parse_value(arg, iter.next())?,
+                "--width" => config.width = parse_value(arg, iter.next())?,
+                "--height" => config.height = parse_value(arg, iter.next())?,
+                "--size" => {
+                    let value: String = parse_value(arg, iter.next())?;
+                    let (width, height) = value
+                        .split_once('x')
+                        .ok_or_else(|| format!("Invalid value for --size: {} (expected WIDTHxHEIGHT)", value))?;
+                    config.width = parse_value(arg, Some(&width.to_string()))?;
+                    config.height = parse_value(arg, Some(&height.to_string()))?;
+                }
+                "--output" => config.output_dir = PathBuf::from(parse_value::<String>(arg, iter.next())?),
+                "--start-time" => config.start_time = parse_value(arg, iter.next())?,
+                "--time-step" => config.time_step = parse_value(arg, iter.next())?,
+                "--follow" => config.follow = Some(parse_value(arg, iter.next())?),
+                other => {
+                    if !config.scene.parse_flag(other, &mut iter)? {
+                        return Err(format!("Unknown argument: {}", other));
+                    }
+                }
+            }
+        }
+
+        if config.width == 0 || config.height == 0 {
+            return Err("Resolution must be greater than zero".to_string());
+        }
+
+        Ok(config)
+    }
+
+    /// Ruta del archivo de imagen para un cuadro dado
+    pub fn frame_path(&self, frame: usize) -> PathBuf {
+        self.output_dir.join(format!("frame_{:04}.png", frame))
+    }
+}
+
+/// Renderiza la escena cuadro por cuadro y guarda cada uno como PNG, sin abrir ventana
+pub fn run(config: &HeadlessConfig) -> Result<(), String> {
+    std::fs::create_dir_all(&config.output_dir)
+        .map_err(|e| format!("Failed to create {}: {}", config.output_dir.display(), e))?;
+
+    let mut framebuffer = Framebuffer::new(config.width, config.height);
+    framebuffer.set_background_color(0x000000);
+    framebuffer.set_transparency_mode(config.scene.transparency);
+
+    let mut camera = Camera::new(
+        Vec3::new(0.0, 0.0, 10.0), // Eye
+        Vec3::new(0.0, 0.0, 0.0),  // Center
+        Vec3::new(0.0, 1.0, 0.0),  // Up
+    );
+    let star_field = StarField::with_seed(3000, STAR_FIELD_SEED);
+    let star_field = create_stars(config.scene.star_catalog.as_deref(), star_field)?;
+    let skybox = create_skybox();
+    let mut body_manager = create_body_manager();
+    bake_surfaces(&mut body_manager, &config.scene)?;
+
+    if let Some(index) = config.follow {
+        if index >= body_manager.all_bodies.len() {
+            return Err(format!("Body index {} out of range", index));
+        }
+        body_manager.select(index);
+    }
+    let mut effects = SceneEffects::new(&body_manager, camera.eye, config.start_time);
+
+    for frame in 0..config.frames {
+        let time = config.start_time + frame as f32 * config.time_step;
+        if frame > 0 {
+            effects.update(&body_manager, camera.eye, time, config.time_step);
+        }
+
+        // Seguir al cuerpo seleccionado igual que con las teclas numéricas
+        if config.follow.is_some() {
+            let selected_body = &body_manager.all_bodies[body_manager.current_index];
+            camera.follow(body_manager.get_body_position(selected_body, time));
+        }
+
+        render_scene(&mut framebuffer, &mut body_manager, &effects, &skybox, &star_field, &camera, time);
+
+        let path = config.frame_path(frame);
+        framebuffer
+            .save_png(&path)
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,150 @@
+// src/lighting.rs
+
+use nalgebra_glm::{Vec3, dot};
+use crate::color::Color;
+use crate::fragment::Fragment;
+use crate::uniforms::Uniforms;
+
+/// Color de la luz del sol (blanco cálido)
+pub const SUN_COLOR: Color = Color::new(255, 244, 229);
+/// Intensidad del sol: a esta distancia al cuadrado la irradiancia vale 1.0
+pub const SUN_INTENSITY: f32 = 64.0;
+
+/// Luz puntual con caída según el inverso del cuadrado de la distancia
+#[derive(Clone, Copy, Debug)]
+pub struct PointLight {
+    pub position: Vec3,
+    pub color: Color,
+    pub intensity: f32,
+    pub radius: f32, // Radio físico de la estrella, para las penumbras
+}
+
+impl PointLight {
+    pub fn new(position: Vec3, color: Color, intensity: f32, radius: f32) -> Self {
+        PointLight {
+            position,
+            color,
+            intensity,
+            radius,
+        }
+    }
+
+    /// Dirección normalizada desde el punto hacia la luz y la irradiancia que llega a él
+    pub fn incident(&self, world_position: &Vec3) -> (Vec3, f32) {
+        let to_light = self.position - world_position;
+        let distance_squared = to_light.magnitude_squared().max(1e-4);
+        let direction = to_light / distance_squared.sqrt();
+        (direction, self.intensity / distance_squared)
+    }
+
+    /// Exposición: comprime la irradiancia para que los planetas interiores no se saturen
+    /// y los exteriores sigan siendo visibles, conservando la caída con la distancia
+    pub fn exposure_at(&self, world_position: &Vec3) -> f32 {
+        let (_, irradiance) = self.incident(world_position);
+        1.0 - (-irradiance).exp()
+    }
+}
+
+/// Parámetros de iluminación de un material
+#[derive(Clone, Copy, Debug)]
+pub struct LightingParams {
+    pub ambient: f32,     // Luz ambiental (fracción del color base)
+    pub specular: f32,    // Intensidad del brillo especular (0 = mate)
+    pub shininess: f32,   // Exponente de Blinn-Phong
+    pub rim: f32,         // Intensidad del borde de Fresnel (0 = sin borde)
+    pub rim_power: f32,   // Qué tan pegado al limbo queda el borde
+    pub rim_color: Color,
+}
+
+impl LightingParams {
+    /// Superficie mate con un poco de luz ambiental
+    pub const fn matte() -> Self {
+        LightingParams {
+            ambient: 0.04,
+            specular: 0.0,
+            shininess: 1.0,
+            rim: 0.0,
+            rim_power: 1.0,
+            rim_color: Color::black(),
+        }
+    }
+
+    pub const fn with_specular(mut self, specular: f32, shininess: f32) -> Self {
+        self.specular = specular;
+        self.shininess = shininess;
+        self
+    }
+
+    pub const fn with_rim(mut self, rim: f32, rim_power: f32, rim_color: Color) -> Self {
+        self.rim = rim;
+        self.rim_power = rim_power;
+        self.rim_color = rim_color;
+        self
+    }
+}
+
+/// Interpolación suave de Hermite entre dos bordes
+pub fn smoothstep(edge0: f32, edge1: f32, x: f32) -> f32 {
+    let t = ((x - edge0) / (edge1 - edge0)).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+/// Término difuso de Lambert
+pub fn lambert(normal: &Vec3, light_dir: &Vec3) -> f32 {
+    dot(normal, light_dir).max(0.0)
+}
+
+/// Término especular de Blinn-Phong
+pub fn blinn_phong(normal: &Vec3, light_dir: &Vec3, view_dir: &Vec3, shininess: f32) -> f32 {
+    let half_vector = (light_dir + view_dir).normalize();
+    dot(normal, &half_vector).max(0.0).powf(shininess)
+}
+
+/// Aproximación de Schlick: vale 0 de frente y 1 en el limbo
+pub fn fresnel(normal: &Vec3, view_dir: &Vec3, power: f32) -> f32 {
+    (1.0 - dot(normal, view_dir).clamp(0.0, 1.0)).powf(power)
+}
+
+/// Ilumina un color base con la luz puntual de las uniformes: ambiente + difuso + especular + borde
+pub fn shade(base: Color, fragment: &Fragment, uniforms: &Uniforms, params: &LightingParams) -> Color {
+    shade_normal(base, &fragment.normal, fragment, uniforms, params)
+}
+
+/// Igual que `shade`, pero con una normal propia (caras dobles, normales perturbadas)
+pub fn shade_normal(base: Color, normal: &Vec3, fragment: &Fragment, uniforms: &Uniforms, params: &LightingParams) -> Color {
+    let normal = normal.normalize();
+    let light = &uniforms.lighting.light;
+    let (light_dir, _) = light.incident(&fragment.world_position);
+    let view_dir = (uniforms.lighting.camera_position - fragment.world_position).normalize();
+    let exposure = light.exposure_at(&fragment.world_position);
+
+    // Sombras de otros cuerpos (eclipses, tránsitos y anillos) y de las nubes propias;
+    // solo en el lado iluminado
+    let mut diffuse = lambert(&normal, &light_dir) * exposure;
+    let visibility = if diffuse > 0.0 {
+        let clouds = uniforms
+            .appearance
+            .clouds
+            .map_or(1.0, |clouds| clouds.transmittance(&uniforms.noise, &fragment.world_position, light));
+        uniforms.lighting.shadows.visibility(&fragment.world_position, light) * clouds
+    } else {
+        1.0
+    };
+    diffuse *= visibility;
+
+    let mut color = base * params.ambient + base.blend_multiply(&light.color) * diffuse;
+
+    if params.specular > 0.0 && diffuse > 0.0 {
+        let specular = blinn_phong(&normal, &light_dir, &view_dir, params.shininess);
+        color = color + light.color * (specular * params.specular * exposure * visibility);
+    }
+
+    if params.rim > 0.0 {
+        // El borde solo brilla en el lado iluminado y se desvanece en el terminador
+        let daylight = (dot(&normal, &light_dir) + 0.5).clamp(0.0, 1.0);
+        let rim = fresnel(&normal, &view_dir, params.rim_power) * params.rim * daylight;
+        color = color + params.rim_color * rim;
+    }
+
+    color
+}
@@ -0,0 +1,128 @@
+// src/lod.rs
+
+use nalgebra_glm::Mat4;
+use crate::assets::{AssetManager, MeshHandle};
+use crate::obj::{Obj, MeshBounds};
+
+/// Subdivisiones del icosaedro en la malla más detallada (5120 triángulos)
+pub const MAX_SUBDIVISIONS: u32 = 4;
+/// Largo en píxeles que se busca para las aristas de la malla en pantalla
+const TARGET_EDGE_PIXELS: f32 = 12.0;
+/// Arista del icosaedro en radios de la esfera circunscrita; se reduce a la mitad con cada subdivisión
+const ICOSAHEDRON_EDGE: f32 = 1.051;
+/// Radio en pantalla (píxeles) por debajo del cual el cuerpo se dibuja como un solo punto
+const IMPOSTOR_RADIUS: f32 = 1.5;
+/// Margen de los umbrales, en niveles de subdivisión y en fracción del radio del impostor,
+/// para que un cuerpo en el límite no alterne de malla en cada cuadro
+const HYSTERESIS: f32 = 0.25;
+
+/// Representación con la que se dibuja un cuerpo en un cuadro
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Lod {
+    Impostor,    // Un punto de luz en lugar de la malla
+    Mesh(usize), // Nivel de la cadena de mallas, desde 0 (la más simple)
+}
+
+/// Mallas de un mismo cuerpo de la más simple a la más detallada
+#[derive(Clone)]
+pub struct LodChain {
+    levels: Vec<MeshHandle>,
+}
+
+impl LodChain {
+    /// Icoesferas con 0 a `max_subdivisions` subdivisiones que ocupan la esfera envolvente
+    /// de `bounds`, así el ruido, las sombras y la escala no cambian respecto al modelo original.
+    /// Se registran en `assets` para que todos los cuerpos las compartan.
+    pub fn icosphere(assets: &mut AssetManager, bounds: &MeshBounds, max_subdivisions: u32) -> Self {
+        let levels = (0..=max_subdivisions)
+            .map(|subdivisions| assets.add_mesh(&Obj::icosphere(bounds.center, bounds.radius, subdivisions)))
+            .collect();
+        LodChain { levels }
+    }
+
+    pub fn level(&self, index: usize) -> MeshHandle {
+        self.levels[index.min(self.levels.len() - 1)]
+    }
+
+    /// Nivel de la malla más detallada
+    pub fn finest(&self) -> Lod {
+        Lod::Mesh(self.levels.len() - 1)
+    }
+
+    pub fn finest_mesh(&self) -> MeshHandle {
+        self.levels[self.levels.len() - 1]
+    }
+
+    /// Nivel para un cuerpo que ocupa `screen_radius` píxeles de radio: el que deja aristas de
+    /// unos `TARGET_EDGE_PIXELS`. Se mantiene `previous` mientras no se salga del umbral por
+    /// más de `HYSTERESIS`.
+    pub fn select(&self, previous: Lod, screen_radius: f32) -> Lod {
+        let impostor = match previous {
+            Lod::Impostor => screen_radius < IMPOSTOR_RADIUS * (1.0 + HYSTERESIS),
+            Lod::Mesh(_) => screen_radius < IMPOSTOR_RADIUS * (1.0 - HYSTERESIS),
+        };
+        if impostor {
+            return Lod::Impostor;
+        }
+
+        // Subdivisiones que harían falta, sin redondear: con `n` la arista mide `edge / 2^n`
+        let finest = self.levels.len() as f32 - 1.0;
+        let detail = (ICOSAHEDRON_EDGE * screen_radius / TARGET_EDGE_PIXELS).max(1e-6).log2();
+        match previous {
+            Lod::Mesh(level) if detail > level as f32 - 1.0 - HYSTERESIS && detail <= level as f32 + HYSTERESIS => {
+                Lod::Mesh(level.min(self.levels.len() - 1))
+            }
+            _ => Lod::Mesh(detail.ceil().clamp(0.0, finest) as usize),
+        }
+    }
+}
+
+/// Píxeles que ocupa una unidad del mundo a distancia 1 de la cámara
+pub fn pixels_per_unit(projection_matrix: &Mat4, viewport_matrix: &Mat4) -> f32 {
+    projection_matrix[(1, 1)] * viewport_matrix[(1, 1)].abs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nalgebra_glm::Vec3;
+
+    fn chain() -> LodChain {
+        let bounds = MeshBounds { center: Vec3::zeros(), radius: 1.0, radial_min: 0.0, radial_max: 1.0 };
+        LodChain::icosphere(&mut AssetManager::new(), &bounds, MAX_SUBDIVISIONS)
+    }
+
+    /// Radio en pantalla con el que harían falta `detail` subdivisiones (inversa de `select`)
+    fn radius_for(detail: f32) -> f32 {
+        TARGET_EDGE_PIXELS / ICOSAHEDRON_EDGE * detail.exp2()
+    }
+
+    #[test]
+    fn select_waits_past_the_upper_threshold_to_refine() {
+        let lods = chain();
+        assert_eq!(lods.select(Lod::Mesh(1), radius_for(1.0 + HYSTERESIS - 0.05)), Lod::Mesh(1));
+        assert_eq!(lods.select(Lod::Mesh(1), radius_for(1.0 + HYSTERESIS + 0.05)), Lod::Mesh(2));
+    }
+
+    #[test]
+    fn select_waits_past_the_lower_threshold_to_coarsen() {
+        let lods = chain();
+        assert_eq!(lods.select(Lod::Mesh(2), radius_for(1.0 - HYSTERESIS + 0.05)), Lod::Mesh(2));
+        assert_eq!(lods.select(Lod::Mesh(2), radius_for(1.0 - HYSTERESIS - 0.05)), Lod::Mesh(1));
+    }
+
+    #[test]
+    fn select_keeps_the_impostor_inside_its_margin() {
+        let lods = chain();
+        assert_eq!(lods.select(Lod::Mesh(0), IMPOSTOR_RADIUS * (1.0 - HYSTERESIS) + 0.05), Lod::Mesh(0));
+        assert_eq!(lods.select(Lod::Mesh(0), IMPOSTOR_RADIUS * (1.0 - HYSTERESIS) - 0.05), Lod::Impostor);
+        assert_eq!(lods.select(Lod::Impostor, IMPOSTOR_RADIUS * (1.0 + HYSTERESIS) - 0.05), Lod::Impostor);
+        assert_eq!(lods.select(Lod::Impostor, IMPOSTOR_RADIUS * (1.0 + HYSTERESIS) + 0.05), Lod::Mesh(0));
+    }
+
+    #[test]
+    fn select_clamps_to_the_finest_level() {
+        let lods = chain();
+        assert_eq!(lods.select(Lod::Mesh(0), radius_for(MAX_SUBDIVISIONS as f32 + 3.0)), lods.finest());
+    }
+}
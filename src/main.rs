@@ -1,7 +1,6 @@
 use nalgebra_glm::{Vec3, Vec4, Mat4};
 use minifb::{Key, Window, WindowOptions, MouseButton, MouseMode};
 use std::time::Duration;
-use std::sync::Arc;
 
 // Importa tus módulos aquí
 mod framebuffer;
@@ -17,22 +16,52 @@ mod uniforms;
 mod renderer;
 mod orbital;
 mod noise; // Añadido
+mod lighting;
+mod shadows;
+mod atmosphere;
+mod clouds;
+mod rings;
+mod billboard;
+mod comet;
+mod particles;
+mod solar;
+mod skybox;
+mod texture;
+mod bake;
+mod assets;
+mod lod;
+mod effects;
+mod material;
 mod stars; // Añade esta línea
+mod catalog;
+mod options;
+mod headless;
+#[cfg(test)]
+mod golden_tests;
 
 use stars::StarField; // Y esta línea para usar StarField
+use headless::HeadlessConfig;
+use options::SceneOptions;
 
 use framebuffer::{Framebuffer, post_process};
 use vertex::Vertex;
-use obj::Obj;
 use camera::Camera;
 use color::Color;
 use fastnoise_lite::{FastNoiseLite, NoiseType, CellularDistanceFunction, FractalType};
 use renderer::render;
+use billboard::render_billboards;
+use effects::SceneEffects;
+use skybox::{NebulaMaterial, Skybox};
+use texture::SurfaceTextures;
+use bake::BakeCache;
+use assets::{AssetManager, MeshAsset};
+use lod::Lod;
+use fragment::BlendMode;
 use fragment::CelestialType;
-use uniforms::Uniforms;
+use uniforms::{Appearance, SceneLighting, Uniforms, ViewTransform};
+use shadows::ShadowCasters;
 use orbital::{OrbitalElements, CelestialBody, CelestialBodyEnum, BodyManager};
 use noise::{create_noise_star, create_noise_planet, create_noise_gas_giant, create_noise_moon, create_noise_comet, create_noise_nebula}; // Añadido
-// Importa ParticleSystem si es necesario
 const SIZE_SCALE: f32 = 1.0;
 const DISTANCE_SCALE: f32 = 1.0;
 // Función para crear la matriz de modelo
@@ -232,87 +261,59 @@ fn handle_input(
     }
 }
 
-fn main() {
-    let window_width = 800;
-    let window_height = 600;
-    let framebuffer_width = 800;
-    let framebuffer_height = 600;
-    let frame_delay = Duration::from_millis(16); // Aproximadamente 60 FPS
+/// Resuelve la ruta de un recurso: primero relativa al directorio actual y,
+/// si no existe, relativa a `src/` dentro del crate (útil para `cargo run` y los tests)
+fn asset_path(relative: &str) -> String {
+    if std::path::Path::new(relative).exists() {
+        relative.to_string()
+    } else {
+        format!("{}/src/{}", env!("CARGO_MANIFEST_DIR"), relative)
+    }
+}
 
-    let mut framebuffer = Framebuffer::new(framebuffer_width, framebuffer_height);
-    let mut window = Window::new(
-        "Animated Fragment Shader",
-        window_width,
-        window_height,
-        WindowOptions::default(),
-    )
-    .unwrap_or_else(|e| {
-        panic!("Failed to create window: {}", e);
-    });
+/// Estrellas del fondo: el catálogo de `catalog_path` si se indica, si no un campo aleatorio
+fn create_stars(catalog_path: Option<&std::path::Path>, star_field: StarField) -> Result<StarField, String> {
+    match catalog_path {
+        Some(path) => StarField::from_catalog(path),
+        None => Ok(star_field),
+    }
+}
 
-    window.set_position(500, 500);
-    window.update();
+/// Resolución de cada cara del cubemap del fondo
+const SKYBOX_RESOLUTION: usize = 256;
 
-    framebuffer.set_background_color(0x000000);
+/// Hornea el fondo de nebulosas y Vía Láctea una sola vez al arrancar
+fn create_skybox() -> Skybox {
+    Skybox::bake(&create_noise_nebula(), &NebulaMaterial::milky_way(), SKYBOX_RESOLUTION)
+}
 
-    let mut is_dragging = false;
-    let mut last_mouse_pos = (0.0, 0.0);
-    // Parámetros de la cámara
-    let mut camera = Camera::new(
-        Vec3::new(0.0, 0.0, 10.0), // Eye
-        Vec3::new(0.0, 0.0, 0.0),  // Center
-        Vec3::new(0.0, 1.0, 0.0),  // Up
-    );
-    let star_field = StarField::new(1000, 100.0); // 1000 estrellas dentro de un radio de 100 unidades
+/// Ancho por defecto de los mapas horneados de los planetas rocosos (el alto es la mitad)
+const BAKE_RESOLUTION: u32 = 1024;
+/// Hornea el terreno de los planetas rocosos a texturas, o las lee de la caché, para no
+/// evaluar el ruido en cada fragmento. Sin resolución (`--no-bake`) se sigue calculando por píxel.
+fn bake_surfaces(body_manager: &mut BodyManager, options: &SceneOptions) -> Result<(), String> {
+    match options.bake_resolution {
+        Some(width) => BakeCache::new(&options.bake_cache).bake_bodies(body_manager, width),
+        None => Ok(()),
+    }
+}
 
+/// Construye el sistema solar: carga la esfera de los cuerpos y genera el ruido de cada uno
+fn create_body_manager() -> BodyManager {
     // Crear generadores de ruido separados para cada cuerpo celeste
     let noise_star = create_noise_star();
     let noise_planet = create_noise_planet();
     let noise_gas_giant = create_noise_gas_giant();
     let noise_moon = create_noise_moon();
     let noise_comet = create_noise_comet();
-    let noise_nebula = create_noise_nebula();
-
-    // Cargar modelos
-    let star_obj = Obj::load("assets/models/planet.obj").expect("Failed to load star.obj");
-    let star_vertex_array = star_obj.get_vertex_array();
-
-    let planet_obj = Obj::load("assets/models/planet.obj").expect("Failed to load planet.obj");
-    let planet_vertex_array = planet_obj.get_vertex_array();
-
-    let gas_giant_obj = Obj::load("assets/models/planet.obj").expect("Failed to load gas_giant.obj");
-    let gas_giant_vertex_array = gas_giant_obj.get_vertex_array();
-
-    let ringed_obj = Obj::load("assets/models/planet.obj").expect("Failed to load ringed.obj");
-    let ringed_vertex_array = ringed_obj.get_vertex_array();
-
-    let rings_obj = Obj::load("assets/models/rings2.obj").expect("Failed to load rings.obj");
-    let rings_vertex_array = rings_obj.get_vertex_array();
-    let planet_obj = Obj::load("assets/models/planet.obj").expect("Failed to load planet.obj");
-    let planet_vertex_array = planet_obj.get_vertex_array();
-    let moon_obj = Obj::load("assets/models/planet.obj").expect("Failed to load moon.obj");
-    let moon_vertex_array = moon_obj.get_vertex_array();
-    let planet2_obj = Obj::load("assets/models/planet.obj").expect("Failed to load planet.obj");
-    let planet2_vertex_array = planet2_obj.get_vertex_array();
-    let mars_obj = Obj::load("assets/models/planet.obj").expect("Failed to load planet.obj");
-    let mars_vertex_array = mars_obj.get_vertex_array();
-    let comet_obj = Obj::load("assets/models/planet.obj").expect("Failed to load comet.obj");
-    let comet_vertex_array = comet_obj.get_vertex_array();
-    let mut time = 0.0; // Usar f32 para mayor precisión en cálculos de tiempo
-    let spaceship_obj = Obj::load("assets/models/ship.obj").expect("Failed to load ship.obj");
-    let spaceship_vertex_array = spaceship_obj.get_vertex_array();
 
-    // Inicializar BodyManager
+    // Cargar el modelo: todos los cuerpos son la misma esfera escalada
+    let mut assets = AssetManager::new();
+    let sphere_mesh = assets.load_mesh(&asset_path("assets/models/planet.obj")).unwrap_or_else(|e| panic!("{}", e));
+
     let mut body_manager = BodyManager::new(
-        star_obj,
-        planet_obj,
-        gas_giant_obj,
-        ringed_obj,
-        rings_obj,
-        planet2_obj,
-        mars_obj,
-        moon_obj,
-        comet_obj,
+        assets,
+        sphere_mesh,
         noise_star,
         noise_planet,
         noise_gas_giant,
@@ -320,131 +321,287 @@ fn main() {
         noise_comet,
     );
 
-    // src/main.rs
+    // Texturas opcionales de cada cuerpo en `assets/textures/<nombre>/` (ver `SurfaceTextures::load_dir`)
+    for body in body_manager.all_bodies.iter_mut() {
+        let directory = asset_path(&format!("assets/textures/{}", body.name.to_lowercase()));
+        body.textures = SurfaceTextures::load_dir(std::path::Path::new(&directory), 1.0)
+            .unwrap_or_else(|e| panic!("{}", e));
+    }
 
+    body_manager
+}
 
+/// Crea las uniformes de un cuerpo celeste para la posición dada
+fn body_uniforms(body: &CelestialBody, position: Vec3, lighting: SceneLighting, view: ViewTransform, time: f32) -> Uniforms {
+    // Crear la matriz de modelo con la posición y rotación propia
+    let model_matrix = body.model_matrix(position, time);
+    let appearance = Appearance {
+        terrain: body.terrain,
+        thresholds: body.thresholds,
+        relief: body.relief(),
+        gas_giant: body.gas_giant.clone(),
+        solar: body.solar.clone(),
+        textures: body.textures.clone(),
+        atmosphere: body.atmosphere_shell(position, time),
+        clouds: body.cloud_shell(position, time),
+        rings: body.rings.clone(),
+    };
+
+    Uniforms::new(model_matrix, body.bounds.center, view, time, body.noise.clone(), lighting, appearance)
+}
 
-while window.is_open() && !window.is_key_down(Key::Escape) {
-    // Actualizar el tiempo
-    time += 0.016; // Aproximadamente 60 FPS
+/// Dibuja la capa de nubes de un cuerpo (si tiene) mezclándola sobre la superficie
+fn render_clouds(framebuffer: &mut Framebuffer, body: &CelestialBody, mesh: &MeshAsset, position: Vec3, uniforms: &mut Uniforms) {
+    if body.clouds.is_none() {
+        return;
+    }
+    let surface_matrix = uniforms.model_matrix;
+    uniforms.model_matrix = body.cloud_model_matrix(position, uniforms.time);
+    render(framebuffer, uniforms, mesh.vertices(), CelestialType::Clouds);
+    uniforms.model_matrix = surface_matrix;
+}
 
-    // Manejar entradas
-    handle_input(
-        &window,
-        &mut camera,
-        &mut body_manager,
-        time,
-        &mut is_dragging,
-        &mut last_mouse_pos,
-    );
+/// Dibuja los anillos de un cuerpo (si tiene) con transparencia, con sus propias sombras
+fn render_rings(framebuffer: &mut Framebuffer, body: &CelestialBody, ring_mesh: Option<&MeshAsset>, position: Vec3, uniforms: &mut Uniforms, shadows: ShadowCasters) {
+    let Some(ring_mesh) = ring_mesh else {
+        return;
+    };
+    let surface_matrix = std::mem::replace(&mut uniforms.model_matrix, body.ring_model_matrix(position));
+    let surface_shadows = std::mem::replace(&mut uniforms.lighting.shadows, shadows);
+    render(framebuffer, uniforms, ring_mesh.vertices(), CelestialType::Rings);
+    uniforms.model_matrix = surface_matrix;
+    uniforms.lighting.shadows = surface_shadows;
+}
 
-    // Actualizar la posición de la nave
+/// Dibuja la capa atmosférica de un cuerpo (si tiene) de forma aditiva
+fn render_atmosphere(framebuffer: &mut Framebuffer, body: &CelestialBody, mesh: &MeshAsset, position: Vec3, mut uniforms: Uniforms) {
+    if body.atmosphere.is_none() {
+        return;
+    }
+    uniforms.model_matrix = body.atmosphere_model_matrix(position, uniforms.time);
+    render(framebuffer, &uniforms, mesh.vertices(), CelestialType::Atmosphere);
+}
 
+/// Dibuja la coma de un cometa (si lo es) como billboards aditivos; las colas son partículas
+fn render_coma(framebuffer: &mut Framebuffer, body: &CelestialBody, position: Vec3, uniforms: &Uniforms) {
+    let billboards = body.coma_billboards(position, uniforms.lighting.light.position, uniforms.time);
+    render_billboards(
+        framebuffer,
+        &billboards,
+        BlendMode::Additive,
+        &uniforms.view.view_matrix,
+        &uniforms.view.projection_matrix,
+        &uniforms.view.viewport_matrix,
+    );
+}
+
+/// Renderiza un cuadro completo de la escena en el framebuffer
+fn render_scene(
+    framebuffer: &mut Framebuffer,
+    body_manager: &mut BodyManager,
+    effects: &SceneEffects,
+    skybox: &Skybox,
+    star_field: &StarField,
+    camera: &Camera,
+    time: f32,
+) {
     // Limpiar el framebuffer
     framebuffer.clear();
 
     // Crear matrices de transformación
-        let view_matrix = create_view_matrix(camera.eye, camera.center, camera.up);
-        let projection_matrix = create_perspective_matrix(window_width as f32, window_height as f32);
-        let viewport_matrix = create_viewport_matrix(framebuffer_width as f32, framebuffer_height as f32);
-
-
-    // Definir la dirección de la luz
-    let light_direction = Vec3::new(1.0, 1.0, 1.0).normalize();
-
-    // Renderizar solo la estrella
-    let star = &body_manager.all_bodies[0]; // Asumiendo que la estrella es el primer elemento
-    let model_matrix = create_model_matrix(Vec3::zeros(), star.scale, star.rotation);
-    let uniforms = Uniforms::new(
-        model_matrix,
-        view_matrix,
-        projection_matrix,
-        viewport_matrix,
-        time,
-        star.noise.clone(),
-        light_direction,
-        star.noise_scale,
-        star.ocean_threshold,
-        star.continent_threshold,
-        star.mountain_threshold,
-        star.snow_threshold,
-        star.ring_inner_radius,
-        star.ring_outer_radius,
-        star.ring_color,
-        star.ring_opacity,
-        star.ring_frequency,
-        star.ring_wave_speed,
-        star.ring_rotation_matrix,
-    );
-
-
+    let view_matrix = create_view_matrix(camera.eye, camera.center, camera.up);
+    let projection_matrix = create_perspective_matrix(framebuffer.width as f32, framebuffer.height as f32);
+    let viewport_matrix = create_viewport_matrix(framebuffer.width as f32, framebuffer.height as f32);
+    let view = ViewTransform::new(view_matrix, projection_matrix, viewport_matrix);
+
+    // Nivel de detalle de cada cuerpo según el tamaño que ocupa en este cuadro
+    body_manager.update_lod(camera.eye, lod::pixels_per_unit(&projection_matrix, &viewport_matrix), time);
+    let body_manager = &*body_manager;
+
+    // El cielo y las estrellas van primero: todo lo demás se dibuja encima
+    skybox.render(framebuffer, &view_matrix, &projection_matrix, &viewport_matrix);
+    star_field.render(framebuffer, &view_matrix, &projection_matrix, &viewport_matrix, time);
+
+    // La estrella es una luz puntual para todos los cuerpos
+    let light = body_manager.star_light(time);
+
+    // Los cuerpos de uno o dos píxeles son un punto de luz: las estrellas emiten y el resto
+    // se suma como las estrellas del fondo
+    let mut impostors = Vec::new();
+    let mut glowing_impostors = Vec::new();
     for body in &body_manager.all_bodies {
         // Obtener la posición actual del cuerpo
         let position = body_manager.get_body_position(body, time);
-        
-        // Calcular la dirección de la luz (desde el planeta hacia el sol)
-        let light_direction = (-position).normalize(); // Suponiendo que el sol está en (0,0,0)
-        
-        // Crear la matriz de modelo con la posición y rotación propia
-        let model_matrix = create_model_matrix(position, body.scale, body.rotation);
-        
+        if body.lod == Lod::Impostor {
+            let impostor = body.impostor(position, &light, camera.eye, time);
+            match body.shader_type {
+                CelestialType::Star => glowing_impostors.push(impostor),
+                _ => impostors.push(impostor),
+            }
+            continue;
+        }
+
         // Crear las uniformes necesarias para el shader
-        let uniforms = Uniforms::new(
-            model_matrix,
-            view_matrix,
-            projection_matrix,
-            viewport_matrix,
+        let uniforms = body_uniforms(
+            body,
+            position,
+            SceneLighting::new(light, camera.eye, body_manager.shadow_casters(body, time)),
+            view,
             time,
-            body.noise.clone(),
-            light_direction, // Actualizado dinámicamente
-            body.noise_scale,
-            body.ocean_threshold,
-            body.continent_threshold,
-            body.mountain_threshold,
-            body.snow_threshold,
-            body.ring_inner_radius,
-            body.ring_outer_radius,
-            body.ring_color,
-            body.ring_opacity,
-            body.ring_frequency,
-            body.ring_wave_speed,
-            body.ring_rotation_matrix,
         );
-        
+
         // Renderizar el cuerpo celeste
-        render(&mut framebuffer, &uniforms, &body.obj.get_vertex_array(), body.shader_type);
+        render(framebuffer, &uniforms, body_manager.mesh(body).vertices(), body.shader_type);
+    }
+    render_billboards(framebuffer, &impostors, BlendMode::Additive, &view_matrix, &projection_matrix, &viewport_matrix);
+    render_billboards(framebuffer, &glowing_impostors, BlendMode::Emissive, &view_matrix, &projection_matrix, &viewport_matrix);
+
+    // Pase transparente: nubes y anillos se mezclan sobre los cuerpos opacos ya dibujados,
+    // de atrás hacia delante según su distancia a la cámara
+    let layered = |body: &&CelestialBody| {
+        body.clouds.is_some() || body.ring_mesh.is_some() || body.atmosphere.is_some() || body.comet_tails.is_some()
+    };
+    let mut transparent: Vec<(&CelestialBody, Vec3)> = body_manager
+        .all_bodies
+        .iter()
+        .filter(layered)
+        .map(|body| (body, body_manager.get_body_position(body, time)))
+        .collect();
+    transparent.sort_by(|(_, a), (_, b)| {
+        nalgebra_glm::distance(b, &camera.eye).total_cmp(&nalgebra_glm::distance(a, &camera.eye))
+    });
+    let mut layers: Vec<(&CelestialBody, Vec3, Uniforms)> = transparent
+        .into_iter()
+        .map(|(body, position)| {
+            let uniforms = body_uniforms(
+                body,
+                position,
+                SceneLighting::new(light, camera.eye, body_manager.shadow_casters(body, time)),
+                view,
+                time,
+            );
+            (body, position, uniforms)
+        })
+        .collect();
+    for (body, position, uniforms) in &mut layers {
+        if body.lod != Lod::Impostor {
+            let mesh = body_manager.mesh(body);
+            render_clouds(framebuffer, body, mesh, *position, uniforms);
+            render_rings(framebuffer, body, body_manager.ring_mesh(body), *position, uniforms, body_manager.ring_shadow_casters(body, time));
+        }
+    }
+    // Con OIT lo acumulado se compone aquí, antes de que las capas aditivas, las partículas y
+    // las órbitas escriban en el buffer de color
+    framebuffer.resolve_transparency();
+
+    // Pase aditivo: comas y atmósferas no dependen del orden. De un impostor solo se ve la
+    // coma, que es mucho mayor que el cuerpo.
+    for (body, position, uniforms) in layers {
+        render_coma(framebuffer, body, position, &uniforms);
+        if body.lod != Lod::Impostor {
+            render_atmosphere(framebuffer, body, body_manager.mesh(body), position, uniforms);
+        }
     }
- 
+    effects.render(framebuffer, &view_matrix, &projection_matrix, &viewport_matrix);
 
-    // Renderizar las líneas orbitales como puntos
-// Renderizar las líneas orbitales como puntos, excluyendo la estrella
+    // Renderizar las líneas orbitales como puntos, excluyendo la estrella
     for body in &body_manager.all_bodies {
         if body.name != "Star" {
             let orbital_path = generate_orbital_path(&body.orbital_elements);
-            render_orbital_points(&mut framebuffer, &orbital_path, &view_matrix, &projection_matrix, &viewport_matrix);
+            render_orbital_points(framebuffer, &orbital_path, &view_matrix, &projection_matrix, &viewport_matrix);
         }
     }
-    render(&mut framebuffer, &uniforms, &star_vertex_array, CelestialType::Star);
-
- 
-          // Renderizar las líneas orbitales como puntos, excluyendo la estrella
-          for body in &body_manager.all_bodies {
-              if body.name != "Star" {
-                  let orbital_path = generate_orbital_path(&body.orbital_elements);
-                  render_orbital_points(&mut framebuffer, &orbital_path, &view_matrix, &projection_matrix, &viewport_matrix);
-              }
-          }
-          star_field.render(&mut framebuffer, &camera, &projection_matrix, &viewport_matrix);
-
-          // Post-Procesamiento para Emisión (si es necesario)
-          post_process(&mut framebuffer);
-
-          // Actualizar la ventana con el framebuffer
-          window
-              .update_with_buffer(&framebuffer.buffer, framebuffer_width, framebuffer_height)
-              .unwrap();
-  
-          // Control de la tasa de frames
-          std::thread::sleep(frame_delay);
-      }
-  }
\ No newline at end of file
+
+    // Post-Procesamiento para Emisión (si es necesario)
+    post_process(framebuffer);
+}
+
+fn main() {
+    // Modo sin ventana: `--headless` renderiza cuadros directamente a archivos de imagen
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.iter().any(|arg| arg == "--headless") {
+        let config = HeadlessConfig::from_args(&args).unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            std::process::exit(2);
+        });
+        if let Err(e) = headless::run(&config) {
+            eprintln!("Headless render failed: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+    let options = SceneOptions::from_args(&args).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(2);
+    });
+
+    let window_width = 800;
+    let window_height = 600;
+    let framebuffer_width = 800;
+    let framebuffer_height = 600;
+    let frame_delay = Duration::from_millis(16); // Aproximadamente 60 FPS
+
+    let mut framebuffer = Framebuffer::new(framebuffer_width, framebuffer_height);
+    let mut window = Window::new(
+        "Animated Fragment Shader",
+        window_width,
+        window_height,
+        WindowOptions::default(),
+    )
+    .unwrap_or_else(|e| {
+        panic!("Failed to create window: {}", e);
+    });
+
+    window.set_position(500, 500);
+    window.update();
+
+    framebuffer.set_background_color(0x000000);
+    framebuffer.set_transparency_mode(options.transparency);
+
+    let mut is_dragging = false;
+    let mut last_mouse_pos = (0.0, 0.0);
+    // Parámetros de la cámara
+    let mut camera = Camera::new(
+        Vec3::new(0.0, 0.0, 10.0), // Eye
+        Vec3::new(0.0, 0.0, 0.0),  // Center
+        Vec3::new(0.0, 1.0, 0.0),  // Up
+    );
+    let star_field = StarField::new(3000); // 3000 estrellas hasta la magnitud límite a simple vista
+    let star_field = create_stars(options.star_catalog.as_deref(), star_field).unwrap_or_else(|e| panic!("{}", e));
+
+    let skybox = create_skybox();
+
+    let mut time = 0.0; // Usar f32 para mayor precisión en cálculos de tiempo
+
+    // Inicializar BodyManager
+    let mut body_manager = create_body_manager();
+    bake_surfaces(&mut body_manager, &options).unwrap_or_else(|e| panic!("{}", e));
+    let mut effects = SceneEffects::new(&body_manager, camera.eye, time);
+
+    while window.is_open() && !window.is_key_down(Key::Escape) {
+        // Actualizar el tiempo
+        time += 0.016; // Aproximadamente 60 FPS
+        effects.update(&body_manager, camera.eye, time, 0.016);
+
+        // Manejar entradas
+        handle_input(
+            &window,
+            &mut camera,
+            &mut body_manager,
+            time,
+            &mut is_dragging,
+            &mut last_mouse_pos,
+        );
+
+        // Renderizar la escena completa
+        render_scene(&mut framebuffer, &mut body_manager, &effects, &skybox, &star_field, &camera, time);
+
+        // Actualizar la ventana con el framebuffer
+        window
+            .update_with_buffer(&framebuffer.buffer, framebuffer_width, framebuffer_height)
+            .unwrap();
+
+        // Control de la tasa de frames
+        std::thread::sleep(frame_delay);
+    }
+}
@@ -0,0 +1,230 @@
+// src/material.rs
+
+use crate::color::Color;
+use crate::lighting::smoothstep;
+
+/// Material de terreno para planetas rocosos: paleta y luces nocturnas.
+/// Las nubes son una capa aparte (ver `CloudLayer`).
+/// Un planeta rocoso nuevo se define solo con datos, sin un shader propio.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TerrainMaterial {
+    pub ocean_color: Color,
+    pub continent_color: Color,
+    pub mountain_color: Color,
+    pub snow_color: Color,
+    pub rim_color: Color,      // Color del borde de Fresnel (tono de la atmósfera)
+    pub ocean_specular: f32,   // Brillo especular de los océanos (0 = sin agua líquida)
+    pub bump_strength: f32,    // Intensidad del relieve en la iluminación (0 = liso)
+    pub displacement: f32,     // Altura máxima del relieve en la geometría, en fracción del radio (0 = esfera lisa)
+    pub city_lights: Option<CityLights>, // Luces nocturnas sobre los continentes
+}
+
+/// Escala del ruido del terreno y valores de ruido a partir de los cuales empieza cada franja
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TerrainThresholds {
+    pub noise_scale: f32,
+    pub ocean: f32,
+    pub continent: f32,
+    pub mountain: f32,
+    pub snow: f32,
+}
+
+impl TerrainThresholds {
+    pub const fn new(noise_scale: f32, ocean: f32, continent: f32, mountain: f32, snow: f32) -> Self {
+        TerrainThresholds {
+            noise_scale,
+            ocean,
+            continent,
+            mountain,
+            snow,
+        }
+    }
+}
+
+/// Capa nocturna de luces de ciudades: cúmulos de ruido sobre tierra firme que
+/// solo brillan en el lado de noche y se encienden a lo largo del terminador
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CityLights {
+    pub color: Color,
+    pub intensity: f32,     // Brillo máximo de un cúmulo
+    pub scale: f32,         // Escala de ruido de los cúmulos (más alta = ciudades más pequeñas)
+    pub threshold: f32,     // Umbral de ruido a partir del cual hay ciudad
+    pub twilight: f32,      // Ancho de la franja de crepúsculo (en coseno del ángulo solar)
+}
+
+impl CityLights {
+    /// Luz cálida de sodio
+    pub fn sodium() -> Self {
+        CityLights {
+            color: Color::new(255, 190, 110),
+            intensity: 1.2,
+            scale: 20.0,
+            threshold: 0.2,
+            twilight: 0.2,
+        }
+    }
+}
+
+impl TerrainMaterial {
+    /// Océanos azules, continentes verdes y nubes blancas
+    pub fn earth_like() -> Self {
+        TerrainMaterial {
+            ocean_color: Color::new(10, 115, 252),
+            continent_color: Color::new(34, 139, 34),
+            mountain_color: Color::new(97, 77, 63),
+            snow_color: Color::new(255, 250, 250),
+            rim_color: Color::new(173, 216, 230),
+            ocean_specular: 0.6,
+            bump_strength: 1.2,
+            displacement: 0.0,
+            city_lights: Some(CityLights::sodium()),
+        }
+    }
+
+    /// Mares turquesa y costas verdes
+    pub fn ocean_world() -> Self {
+        TerrainMaterial {
+            ocean_color: Color::new(65, 199, 240),
+            continent_color: Color::new(69, 128, 69),
+            mountain_color: Color::new(184, 121, 77),
+            snow_color: Color::new(219, 219, 200),
+            rim_color: Color::new(173, 216, 230),
+            city_lights: None,
+            ..TerrainMaterial::earth_like()
+        }
+    }
+
+    /// Desierto rojizo sin agua líquida
+    pub fn mars_like() -> Self {
+        TerrainMaterial {
+            ocean_color: Color::new(125, 50, 41),
+            continent_color: Color::new(176, 109, 67),
+            mountain_color: Color::new(128, 20, 13),
+            snow_color: Color::new(207, 174, 149),
+            rim_color: Color::new(229, 230, 209),
+            ocean_specular: 0.0,
+            bump_strength: 0.8,
+            displacement: 0.0,
+            city_lights: None,
+        }
+    }
+
+    /// Activa el relieve en la geometría hasta `displacement` radios sobre el nivel del mar.
+    /// Es opcional porque evalúa el ruido en cada vértice de cada cuadro.
+    pub fn with_displacement(mut self, displacement: f32) -> Self {
+        self.displacement = displacement;
+        self
+    }
+}
+
+/// Material de gigante gaseoso: bandas turbulentas con rotación diferencial y tormentas.
+/// Los ángulos están en radianes y las velocidades en radianes por segundo.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GasGiantMaterial {
+    pub bands: Vec<Color>,      // Paleta de las bandas, de un polo al otro se recorre cíclicamente
+    pub band_frequency: f32,    // Número de ciclos de la paleta entre el ecuador y un polo
+    pub turbulence: f32,        // Cuánto deforma el ruido los bordes de las bandas (radianes de latitud)
+    pub warp_scale: f32,        // Escala del ruido que deforma las bandas
+    pub detail_scale: f32,      // Escala de las vetas finas dentro de cada banda
+    pub equator_speed: f32,     // Deriva de las bandas en el ecuador respecto al giro del cuerpo
+    pub jet_speed: f32,         // Amplitud de las corrientes en chorro que alternan con la latitud
+    pub storms: Vec<Vortex>,
+    pub rim_color: Color,
+}
+
+/// Tormenta ciclónica (análoga a la Gran Mancha Roja) que gira y deriva con las bandas
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Vortex {
+    pub latitude: f32,
+    pub longitude: f32,     // Longitud inicial
+    pub radius: f32,        // Radio angular en dirección norte-sur
+    pub aspect: f32,        // Alargamiento este-oeste (1 = circular)
+    pub swirl: f32,         // Cuántas vueltas da el remolino en su centro (radianes)
+    pub spin_speed: f32,    // Velocidad de giro del remolino
+    pub drift_speed: f32,   // Deriva en longitud
+    pub color: Color,
+}
+
+impl GasGiantMaterial {
+    /// Bandas beige y marrones con una gran tormenta roja en el hemisferio sur
+    pub fn jupiter_like() -> Self {
+        GasGiantMaterial {
+            bands: vec![
+                Color::new(232, 216, 188),
+                Color::new(200, 160, 100),
+                Color::new(150, 100, 50),
+                Color::new(222, 184, 135),
+                Color::new(170, 120, 80),
+            ],
+            band_frequency: 3.0,
+            turbulence: 0.12,
+            warp_scale: 120.0,
+            detail_scale: 400.0,
+            equator_speed: 0.15,
+            jet_speed: 0.25,
+            storms: vec![
+                Vortex {
+                    latitude: -0.38,
+                    longitude: -3.4,
+                    radius: 0.16,
+                    aspect: 1.8,
+                    swirl: 4.0,
+                    spin_speed: 0.8,
+                    drift_speed: -0.05,
+                    color: Color::new(200, 80, 40),
+                },
+                Vortex {
+                    latitude: 0.55,
+                    longitude: 2.5,
+                    radius: 0.06,
+                    aspect: 1.4,
+                    swirl: 3.0,
+                    spin_speed: -1.2,
+                    drift_speed: 0.1,
+                    color: Color::new(245, 240, 230),
+                },
+            ],
+            rim_color: Color::new(230, 210, 170),
+        }
+    }
+
+    /// Bandas doradas y suaves, con una tormenta blanca pequeña
+    pub fn saturn_like() -> Self {
+        GasGiantMaterial {
+            bands: vec![
+                Color::new(226, 204, 160),
+                Color::new(210, 180, 140),
+                Color::new(191, 134, 103),
+                Color::new(235, 215, 175),
+            ],
+            band_frequency: 2.5,
+            turbulence: 0.05,
+            equator_speed: 0.25,
+            jet_speed: 0.1,
+            storms: vec![Vortex {
+                latitude: 0.7,
+                longitude: -1.0,
+                radius: 0.07,
+                aspect: 2.0,
+                swirl: 2.5,
+                spin_speed: 1.0,
+                drift_speed: 0.05,
+                color: Color::new(250, 245, 230),
+            }],
+            rim_color: Color::new(235, 220, 190),
+            ..GasGiantMaterial::jupiter_like()
+        }
+    }
+
+    /// Color de la paleta en una posición continua (cíclica) entre bandas, con bordes marcados
+    pub fn band_color(&self, t: f32) -> Color {
+        let count = self.bands.len();
+        if count == 0 {
+            return Color::black();
+        }
+        let position = t.rem_euclid(count as f32);
+        let index = position.floor() as usize % count;
+        let next = (index + 1) % count;
+        self.bands[index].lerp(&self.bands[next], smoothstep(0.3, 0.7, position.fract()))
+    }
+}
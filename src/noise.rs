@@ -1,12 +1,14 @@
 // src/noise.rs
 
-use fastnoise_lite::{FastNoiseLite, NoiseType, CellularDistanceFunction, FractalType};
+use fastnoise_lite::{FastNoiseLite, NoiseType, CellularDistanceFunction, CellularReturnType, FractalType};
 use std::sync::Arc;
 
 pub fn create_noise_star() -> Arc<FastNoiseLite> {
     let mut noise = FastNoiseLite::with_seed(1337);
-    noise.set_noise_type(Some(NoiseType::Perlin)); // Ruido Perlin para variaciones suaves
-    noise.set_frequency(Some(1.0)); // Alta frecuencia para detalles finos
+    noise.set_noise_type(Some(NoiseType::Cellular)); // Ruido celular para la granulación
+    noise.set_cellular_distance_function(Some(CellularDistanceFunction::Euclidean));
+    noise.set_cellular_return_type(Some(CellularReturnType::Distance));
+    noise.set_frequency(Some(1.0));
     Arc::new(noise)
 }
 
@@ -50,5 +52,12 @@ pub fn create_noise_nebula() -> Arc<FastNoiseLite> {
     noise.set_noise_type(Some(NoiseType::Cellular)); // Ruido Cellular para patrones nebulosos
     noise.set_cellular_distance_function(Some(CellularDistanceFunction::EuclideanSq));
     noise.set_frequency(Some(0.1)); // Muy baja frecuencia para grandes estructuras
+
+    // Octavas FBm para que las celdas se deshagan en jirones
+    noise.set_fractal_type(Some(FractalType::FBm));
+    noise.set_fractal_octaves(Some(4));
+    noise.set_fractal_lacunarity(Some(2.0));
+    noise.set_fractal_gain(Some(0.5));
+
     Arc::new(noise)
 }
@@ -7,6 +7,15 @@ pub struct Obj {
     meshes: Vec<Mesh>,
 }
 
+/// Límites del modelo en su espacio local
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MeshBounds {
+    pub center: Vec3,       // Centro de la caja envolvente
+    pub radius: f32,        // Radio de la esfera envolvente alrededor de `center`
+    pub radial_min: f32,    // Distancia mínima al eje Y (radio interior de un anillo)
+    pub radial_max: f32,    // Distancia máxima al eje Y (radio exterior de un anillo)
+}
+
 #[derive(Clone, PartialEq)]
 struct Mesh {
     vertices: Vec<Vec3>,
@@ -32,6 +41,8 @@ impl Obj {
                 normals: mesh.normals.chunks(3)
                     .map(|n| Vec3::new(n[0], n[1], n[2]))
                     .collect(),
+                // En OBJ `v = 0` es el borde inferior de la imagen; se invierte solo aquí para que
+                // `v = 0` sea la primera fila de `Texture`, igual que en las mallas generadas
                 texcoords: mesh.texcoords.chunks(2)
                     .map(|t| Vec2::new(t[0], 1.0 - t[1]))
                     .collect(),
@@ -42,6 +53,136 @@ impl Obj {
         Ok(Obj { meshes })
     }
 
+    /// Calcula la esfera envolvente y la extensión radial alrededor del eje Y
+    pub fn bounds(&self) -> MeshBounds {
+        let positions = self.meshes.iter().flat_map(|mesh| mesh.vertices.iter());
+
+        let mut min = Vec3::repeat(f32::INFINITY);
+        let mut max = Vec3::repeat(f32::NEG_INFINITY);
+        let mut radial_min = f32::INFINITY;
+        let mut radial_max: f32 = 0.0;
+        for position in positions.clone() {
+            min = min.inf(position);
+            max = max.sup(position);
+            let radial = (position.x * position.x + position.z * position.z).sqrt();
+            radial_min = radial_min.min(radial);
+            radial_max = radial_max.max(radial);
+        }
+
+        let center = (min + max) * 0.5;
+        let radius = positions
+            .map(|position| (position - center).magnitude())
+            .fold(0.0, f32::max);
+
+        MeshBounds {
+            center,
+            radius,
+            radial_min,
+            radial_max,
+        }
+    }
+
+    /// Genera un anillo plano (corona circular) en el plano XZ, centrado en el origen
+    pub fn annulus(inner_radius: f32, outer_radius: f32, radial_segments: u32, angular_segments: u32) -> Self {
+        let mut vertices = Vec::new();
+        let mut normals = Vec::new();
+        let mut texcoords = Vec::new();
+        let mut indices = Vec::new();
+
+        for ring in 0..=radial_segments {
+            let u = ring as f32 / radial_segments as f32;
+            let radius = inner_radius + (outer_radius - inner_radius) * u;
+            for segment in 0..=angular_segments {
+                let v = segment as f32 / angular_segments as f32;
+                let angle = v * 2.0 * std::f32::consts::PI;
+                vertices.push(Vec3::new(radius * angle.cos(), 0.0, radius * angle.sin()));
+                normals.push(Vec3::new(0.0, 1.0, 0.0));
+                texcoords.push(Vec2::new(u, v));
+            }
+        }
+
+        let row = angular_segments + 1;
+        for ring in 0..radial_segments {
+            for segment in 0..angular_segments {
+                let a = ring * row + segment;
+                let b = a + row;
+                indices.extend_from_slice(&[a, b, a + 1, a + 1, b, b + 1]);
+            }
+        }
+
+        Obj {
+            meshes: vec![Mesh {
+                vertices,
+                normals,
+                texcoords,
+                indices,
+            }],
+        }
+    }
+
+    /// Genera una esfera subdividiendo un icosaedro `subdivisions` veces (20 · 4^n triángulos),
+    /// con coordenadas UV equirectangulares: `u` crece hacia el este desde el meridiano +X y
+    /// `v` va del polo norte (0) al sur (1)
+    pub fn icosphere(center: Vec3, radius: f32, subdivisions: u32) -> Self {
+        let phi = (1.0 + 5.0f32.sqrt()) / 2.0;
+        let mut directions: Vec<Vec3> = [
+            (-1.0, phi, 0.0), (1.0, phi, 0.0), (-1.0, -phi, 0.0), (1.0, -phi, 0.0),
+            (0.0, -1.0, phi), (0.0, 1.0, phi), (0.0, -1.0, -phi), (0.0, 1.0, -phi),
+            (phi, 0.0, -1.0), (phi, 0.0, 1.0), (-phi, 0.0, -1.0), (-phi, 0.0, 1.0),
+        ]
+        .iter()
+        .map(|&(x, y, z)| Vec3::new(x, y, z).normalize())
+        .collect();
+        let mut faces: Vec<[u32; 3]> = vec![
+            [0, 11, 5], [0, 5, 1], [0, 1, 7], [0, 7, 10], [0, 10, 11],
+            [1, 5, 9], [5, 11, 4], [11, 10, 2], [10, 7, 6], [7, 1, 8],
+            [3, 9, 4], [3, 4, 2], [3, 2, 6], [3, 6, 8], [3, 8, 9],
+            [4, 9, 5], [2, 4, 11], [6, 2, 10], [8, 6, 7], [9, 8, 1],
+        ];
+
+        // Cada triángulo se parte en cuatro; los puntos medios de las aristas compartidas
+        // se reutilizan para que la malla quede cerrada
+        for _ in 0..subdivisions {
+            let mut midpoints = std::collections::HashMap::new();
+            let mut midpoint = |a: u32, b: u32| {
+                *midpoints.entry((a.min(b), a.max(b))).or_insert_with(|| {
+                    directions.push((directions[a as usize] + directions[b as usize]).normalize());
+                    directions.len() as u32 - 1
+                })
+            };
+            faces = faces
+                .iter()
+                .flat_map(|&[a, b, c]| {
+                    let (ab, bc, ca) = (midpoint(a, b), midpoint(b, c), midpoint(c, a));
+                    [[a, ab, ca], [b, bc, ab], [c, ca, bc], [ab, bc, ca]]
+                })
+                .collect();
+        }
+
+        // Los vértices no se comparten entre triángulos: los que cruzan el meridiano donde `u`
+        // vuelve a 0 o tocan un polo necesitan sus propias UV
+        let mut vertices = Vec::with_capacity(faces.len() * 3);
+        let mut normals = Vec::with_capacity(faces.len() * 3);
+        let mut texcoords = Vec::with_capacity(faces.len() * 3);
+        for face in &faces {
+            let corners = face.map(|index| directions[index as usize]);
+            for (direction, uv) in corners.iter().zip(equirectangular_uvs(&corners)) {
+                vertices.push(center + direction * radius);
+                normals.push(*direction);
+                texcoords.push(uv);
+            }
+        }
+
+        Obj {
+            meshes: vec![Mesh {
+                indices: (0..vertices.len() as u32).collect(),
+                vertices,
+                normals,
+                texcoords,
+            }],
+        }
+    }
+
     pub fn get_vertex_array(&self) -> Vec<Vertex> {
         let mut vertices = Vec::new();
 
@@ -62,3 +203,26 @@ impl Obj {
         vertices
     }
 }
+
+/// UV equirectangulares de las esquinas de un triángulo de la esfera. Si el triángulo cruza
+/// el meridiano donde `u` vuelve a 0, las `u` pequeñas pasan de 1 para no recorrer todo el mapa al revés;
+/// en un polo la longitud no está definida y se toma la media de las otras dos esquinas.
+fn equirectangular_uvs(corners: &[Vec3; 3]) -> [Vec2; 3] {
+    let longitude = |direction: &Vec3| (-direction.z).atan2(direction.x) / std::f32::consts::TAU;
+    let at_pole = |direction: &Vec3| direction.y.abs() > 1.0 - 1e-6;
+
+    let mut u = corners.map(|direction| longitude(&direction).rem_euclid(1.0));
+    let spanned: Vec<f32> = (0..3).filter(|&i| !at_pole(&corners[i])).map(|i| u[i]).collect();
+    let wraps = spanned.iter().fold(f32::MIN, |a, &b| a.max(b)) - spanned.iter().fold(f32::MAX, |a, &b| a.min(b)) > 0.5;
+    if wraps {
+        for value in u.iter_mut().filter(|value| **value < 0.5) {
+            *value += 1.0;
+        }
+    }
+    for i in (0..3).filter(|&i| at_pole(&corners[i])) {
+        let others: Vec<f32> = (0..3).filter(|&j| j != i).map(|j| u[j]).collect();
+        u[i] = (others[0] + others[1]) * 0.5;
+    }
+
+    [0, 1, 2].map(|i| Vec2::new(u[i], corners[i].y.clamp(-1.0, 1.0).acos() / std::f32::consts::PI))
+}
@@ -0,0 +1,64 @@
+// src/options.rs
+
+use std::path::PathBuf;
+use crate::framebuffer::TransparencyMode;
+use crate::BAKE_RESOLUTION;
+use crate::bake::BakeCache;
+
+/// Opciones de la escena comunes al modo con ventana y al modo sin ventana
+pub struct SceneOptions {
+    pub transparency: TransparencyMode,
+    pub star_catalog: Option<PathBuf>, // Catálogo de estrellas reales en lugar del campo aleatorio
+    pub bake_resolution: Option<u32>,  // Ancho de los mapas horneados del terreno (`None` = ruido por píxel)
+    pub bake_cache: PathBuf,           // Carpeta donde se guardan los mapas horneados
+}
+
+impl Default for SceneOptions {
+    fn default() -> Self {
+        SceneOptions {
+            transparency: TransparencyMode::Sorted,
+            star_catalog: None,
+            bake_resolution: Some(BAKE_RESOLUTION),
+            bake_cache: BakeCache::default_dir(),
+        }
+    }
+}
+
+impl SceneOptions {
+    /// Interpreta los argumentos del modo con ventana, por ejemplo:
+    /// `--oit --star-catalog estrellas.csv --bake-resolution 2048 --bake-cache /tmp/bakes`
+    pub fn from_args(args: &[String]) -> Result<Self, String> {
+        let mut options = SceneOptions::default();
+        let mut iter = args.iter();
+
+        while let Some(arg) = iter.next() {
+            if !options.parse_flag(arg, &mut iter)? {
+                return Err(format!("Unknown argument: {}", arg));
+            }
+        }
+
+        Ok(options)
+    }
+
+    /// Aplica `arg` si es una opción de la escena, tomando su valor de `rest` si lleva uno.
+    /// Devuelve `false` si no lo es, para que quien llama pruebe con las suyas.
+    pub fn parse_flag(&mut self, arg: &str, rest: &mut std::slice::Iter<String>) -> Result<bool, String> {
+        match arg {
+            // `--oit` cambia la mezcla ordenada por transparencia independiente del orden
+            "--oit" => self.transparency = TransparencyMode::WeightedBlended,
+            "--star-catalog" => self.star_catalog = Some(PathBuf::from(parse_value::<String>(arg, rest.next())?)),
+            "--bake-resolution" => self.bake_resolution = Some(parse_value(arg, rest.next())?),
+            "--no-bake" => self.bake_resolution = None,
+            "--bake-cache" => self.bake_cache = PathBuf::from(parse_value::<String>(arg, rest.next())?),
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+}
+
+pub fn parse_value<T: std::str::FromStr>(flag: &str, value: Option<&String>) -> Result<T, String> {
+    let value = value.ok_or_else(|| format!("Missing value for {}", flag))?;
+    value
+        .parse()
+        .map_err(|_| format!("Invalid value for {}: {}", flag, value))
+}
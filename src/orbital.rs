@@ -5,9 +5,21 @@ use nalgebra_glm::Vec4;
 use nalgebra_glm::Mat4;
 use std::sync::Arc;
 use fastnoise_lite::FastNoiseLite;
-use crate::obj::Obj;
+use crate::obj::{Obj, MeshBounds};
+use crate::assets::{AssetManager, MeshAsset, MeshHandle};
 use crate::color::Color;
 use crate::fragment::CelestialType;
+use crate::lighting::{PointLight, SUN_COLOR, SUN_INTENSITY};
+use crate::shadows::{ShadowCasters, SphereOccluder, RingOccluder};
+use crate::atmosphere::{Atmosphere, AtmosphereShell};
+use crate::material::{TerrainMaterial, TerrainThresholds, GasGiantMaterial};
+use crate::clouds::{CloudLayer, CloudShell};
+use crate::rings::{RingProfile, RingSystem};
+use crate::comet::{self, CometTails};
+use crate::solar::SolarMaterial;
+use crate::texture::SurfaceTextures;
+use crate::billboard::Billboard;
+use crate::lod::{Lod, LodChain, MAX_SUBDIVISIONS};
 
 #[derive(Clone, PartialEq)]
 pub struct OrbitalElements {
@@ -40,6 +52,11 @@ impl OrbitalElements {
             orbital_period,
         }
     }
+    /// Distancia mínima al cuerpo central
+    pub fn perihelion(&self) -> f32 {
+        self.semi_major_axis * (1.0 - self.eccentricity)
+    }
+
  /// Calcula la anomalía excéntrica usando el método de Newton-Raphson
  fn eccentric_anomaly(&self, M: f32) -> f32 {
     let mut E = M;
@@ -56,7 +73,12 @@ fn true_anomaly(&self, E: f32) -> f32 {
 
 /// Calcula la posición 3D en un tiempo dado
 pub fn position_at(&self, time: f32) -> Vec3 {
-    let n = 2.0 * std::f32::consts::PI / self.orbital_period; // Movimiento angular medio
+    // Movimiento angular medio (cero para cuerpos que no orbitan, como la estrella)
+    let n = if self.orbital_period > 0.0 {
+        2.0 * std::f32::consts::PI / self.orbital_period
+    } else {
+        0.0
+    };
     let M = self.mean_anomaly + n * time; // Anomalía media
 
     let E = self.eccentric_anomaly(M); // Anomalía excéntrica
@@ -108,76 +130,332 @@ pub fn position_at(&self, time: f32) -> Vec3 {
 #[derive(Clone)]
 pub struct CelestialBody {
     pub name: String,
-    pub obj: Obj,
     pub shader_type: CelestialType,
     pub orbital_elements: OrbitalElements,
     pub scale: f32,
     pub rotation: Vec3,
     pub noise: Arc<FastNoiseLite>,
-    pub noise_scale: f32,
-    pub ocean_threshold: f32,
-    pub continent_threshold: f32,
-    pub mountain_threshold: f32,
-    pub snow_threshold: f32,
-    pub ring_inner_radius: f32,
-    pub ring_outer_radius: f32,
-    pub ring_color: Color,
-    pub ring_opacity: f32,
-    pub ring_frequency: f32,
-    pub ring_wave_speed: f32,
-    pub ring_rotation_matrix: Mat4,
+    pub thresholds: TerrainThresholds, // Escala del ruido y umbrales de las franjas del terreno
+    pub rings: Option<RingSystem>,    // Anillos del cuerpo, si tiene
     pub is_moon: bool,                // Nuevo campo
     pub orbiting_body_name: String,   // Nombre del cuerpo alrededor del cual orbita, si es una luna
+    pub bounds: MeshBounds,           // Límites del modelo, para sombras y tamaño aparente
+    pub atmosphere: Option<Atmosphere>, // Capa atmosférica opcional
+    pub terrain: Option<TerrainMaterial>, // Material de terreno de los planetas rocosos
+    pub axial_tilt: f32,              // Inclinación del eje de giro en radianes
+    pub rotation_period: f32,         // Periodo de rotación sideral en segundos (0 = no gira)
+    pub rotation_phase: f32,          // Ángulo de rotación inicial en radianes
+    pub clouds: Option<CloudLayer>,   // Capa de nubes independiente de la superficie
+    pub gas_giant: Option<GasGiantMaterial>, // Bandas y tormentas de los gigantes gaseosos
+    pub solar: Option<SolarMaterial>, // Fotosfera, manchas y actividad de las estrellas
+    pub textures: Option<SurfaceTextures>, // Mapas de imagen que reemplazan o se mezclan con el ruido
+    pub ring_mesh: Option<MeshHandle>, // Corona generada con los radios de los anillos (en radios del cuerpo)
+    pub comet_tails: Option<CometTails>, // Coma y colas de iones y de polvo
+    pub lods: LodChain,               // Mallas por nivel de detalle en el `AssetManager` de `BodyManager`
+    pub lod: Lod,                     // Nivel elegido en el último cuadro (ver `BodyManager::update_lod`)
 }
 
 impl CelestialBody {
+    /// Cuerpo sin giro propio, anillos ni materiales: se completan con los métodos `with_*`
     pub fn new(
         name: String,
-        obj: Obj,
+        lods: LodChain,
+        bounds: MeshBounds,
         shader_type: CelestialType,
         orbital_elements: OrbitalElements,
         scale: f32,
-        rotation: Vec3,
         noise: Arc<FastNoiseLite>,
-        noise_scale: f32,
-        ocean_threshold: f32,
-        continent_threshold: f32,
-        mountain_threshold: f32,
-        snow_threshold: f32,
-        ring_inner_radius: f32,
-        ring_outer_radius: f32,
-        ring_color: Color,
-        ring_opacity: f32,
-        ring_frequency: f32,
-        ring_wave_speed: f32,
-        ring_rotation_matrix: Mat4,
-        is_moon: bool,                // Añadido
-        orbiting_body_name: String,   // Añadido
     ) -> Self {
         CelestialBody {
             name,
-            obj,
             shader_type,
             orbital_elements,
             scale,
-            rotation,
+            rotation: Vec3::zeros(),
             noise,
-            noise_scale,
-            ocean_threshold,
-            continent_threshold,
-            mountain_threshold,
-            snow_threshold,
-            ring_inner_radius,
-            ring_outer_radius,
-            ring_color,
-            ring_opacity,
-            ring_frequency,
-            ring_wave_speed,
-            ring_rotation_matrix,
-            is_moon,
-            orbiting_body_name,
+            thresholds: TerrainThresholds::default(),
+            rings: None,
+            is_moon: false,
+            orbiting_body_name: String::new(),
+            bounds,
+            atmosphere: None,
+            terrain: None,
+            axial_tilt: 0.0,
+            rotation_period: 0.0,
+            rotation_phase: 0.0,
+            clouds: None,
+            gas_giant: None,
+            solar: None,
+            textures: None,
+            ring_mesh: None,
+            comet_tails: None,
+            lod: lods.finest(),
+            lods,
+        }
+    }
+
+    /// Convierte el cuerpo en una luna de `parent`: su órbita se mide desde ese cuerpo
+    pub fn orbiting(mut self, parent: &str) -> Self {
+        self.is_moon = true;
+        self.orbiting_body_name = parent.to_string();
+        self
+    }
+
+    /// Define la escala del ruido y los umbrales del terreno
+    pub fn with_thresholds(mut self, thresholds: TerrainThresholds) -> Self {
+        self.thresholds = thresholds;
+        self
+    }
+
+    /// Define el giro del cuerpo sobre su propio eje
+    pub fn with_rotation(mut self, axial_tilt: f32, rotation_period: f32, rotation_phase: f32) -> Self {
+        self.axial_tilt = axial_tilt;
+        self.rotation_period = rotation_period;
+        self.rotation_phase = rotation_phase;
+        self
+    }
+
+    /// Ángulo de rotación sideral en un tiempo dado
+    pub fn rotation_angle(&self, time: f32) -> f32 {
+        spin_angle(self.rotation_phase, self.rotation_period, time)
+    }
+
+    /// Asigna el material de terreno (paleta y luces nocturnas) de un planeta rocoso
+    pub fn with_terrain(mut self, terrain: TerrainMaterial) -> Self {
+        self.terrain = Some(terrain);
+        self
+    }
+
+    /// Asigna la paleta, las corrientes y las tormentas de un gigante gaseoso
+    pub fn with_gas_giant(mut self, gas_giant: GasGiantMaterial) -> Self {
+        self.gas_giant = Some(gas_giant);
+        self
+    }
+
+    /// Asigna la fotosfera, las manchas y la actividad de una estrella
+    pub fn with_solar(mut self, solar: SolarMaterial) -> Self {
+        self.solar = Some(solar);
+        self
+    }
+
+    /// Añade anillos al cuerpo y registra en `assets` la corona con la que se dibujan
+    pub fn with_rings(mut self, rings: RingSystem, assets: &mut AssetManager) -> Self {
+        let annulus = Obj::annulus(rings.inner_radius, rings.outer_radius, 24, 128);
+        self.ring_mesh = Some(assets.add_mesh(&annulus));
+        self.rings = Some(rings);
+        self
+    }
+
+    /// Añade una capa atmosférica al cuerpo
+    pub fn with_atmosphere(mut self, atmosphere: Atmosphere) -> Self {
+        self.atmosphere = Some(atmosphere);
+        self
+    }
+
+    /// Añade una capa de nubes que gira a su propio ritmo sobre la superficie
+    pub fn with_clouds(mut self, clouds: CloudLayer) -> Self {
+        self.clouds = Some(clouds);
+        self
+    }
+
+    /// Convierte el cuerpo en un cometa activo con coma y colas
+    pub fn with_comet_tails(mut self, comet_tails: CometTails) -> Self {
+        self.comet_tails = Some(comet_tails);
+        self
+    }
+
+    /// Malla con la que se dibuja el cuerpo en el nivel de detalle actual
+    pub fn current_mesh(&self) -> MeshHandle {
+        match self.lod {
+            Lod::Mesh(level) => self.lods.level(level),
+            Lod::Impostor => self.lods.level(0),
         }
     }
+
+    /// Malla más detallada del cuerpo, la que se usa al hornear sus texturas
+    pub fn detailed_mesh(&self) -> MeshHandle {
+        self.lods.finest_mesh()
+    }
+
+    /// Punto de luz que sustituye al cuerpo cuando ocupa menos de un par de píxeles. Las
+    /// estrellas brillan con su color; el resto refleja su color medio según la fracción del
+    /// disco iluminada vista desde `eye`.
+    pub fn impostor(&self, position: Vec3, light: &PointLight, eye: Vec3, time: f32) -> Billboard {
+        let sphere = self.sphere_occluder(position, time);
+        if self.shader_type == CelestialType::Star {
+            let solar = self.solar.clone().unwrap_or_else(SolarMaterial::sun_like);
+            return Billboard::new(sphere.center, sphere.radius, Color::from_temperature(solar.temperature), solar.brightness);
+        }
+
+        let (light_dir, _) = light.incident(&sphere.center);
+        let view_dir = (eye - sphere.center).normalize();
+        let phase = (1.0 + light_dir.dot(&view_dir)) * 0.5;
+        let brightness = phase * light.exposure_at(&sphere.center);
+        Billboard::new(sphere.center, sphere.radius, self.mean_albedo().blend_multiply(&light.color), brightness)
+    }
+
+    /// Color medio de la superficie: el de sus texturas (incluidas las horneadas) o, si no
+    /// tiene, el de su material
+    fn mean_albedo(&self) -> Color {
+        if let Some(albedo) = self.textures.as_ref().and_then(|textures| textures.albedo.as_ref()) {
+            let mean = albedo.mean();
+            return Color::from_float(mean.x, mean.y, mean.z);
+        }
+        if let Some(gas_giant) = self.gas_giant.as_ref().filter(|gas_giant| !gas_giant.bands.is_empty()) {
+            let weight = 1.0 / gas_giant.bands.len() as f32;
+            return gas_giant.bands.iter().fold(Color::black(), |sum, band| sum + *band * weight);
+        }
+        match self.terrain {
+            Some(terrain) => terrain.ocean_color.lerp(&terrain.continent_color, 0.5),
+            None => IMPOSTOR_ROCK_COLOR,
+        }
+    }
+
+    /// Matriz de modelo con el eje inclinado y un ángulo de giro dado, girando
+    /// alrededor del centro de la malla para que el cuerpo no se bambolee
+    fn spun_model_matrix(&self, position: Vec3, angle: f32) -> Mat4 {
+        let tilt = nalgebra_glm::rotation(self.axial_tilt, &Vec3::new(1.0, 0.0, 0.0));
+        let spin = nalgebra_glm::rotation(angle, &Vec3::new(0.0, 1.0, 0.0));
+
+        // La escala es uniforme, así que conmuta con la rotación propia
+        crate::create_model_matrix(position, self.scale, self.rotation)
+            * Mat4::new_translation(&self.bounds.center)
+            * tilt
+            * spin
+            * Mat4::new_translation(&-self.bounds.center)
+    }
+
+    /// Matriz de modelo del cuerpo en la posición dada: el eje inclinado y el giro
+    /// sideral se aplican aquí, así también rotan las normales para la iluminación
+    pub fn model_matrix(&self, position: Vec3, time: f32) -> Mat4 {
+        self.spun_model_matrix(position, self.rotation_angle(time))
+    }
+
+    /// Esfera envolvente del cuerpo en el espacio del mundo
+    pub fn sphere_occluder(&self, position: Vec3, time: f32) -> SphereOccluder {
+        let center = self.model_matrix(position, time) * Vec4::new(self.bounds.center.x, self.bounds.center.y, self.bounds.center.z, 1.0);
+        SphereOccluder {
+            center: center.xyz(),
+            radius: self.bounds.radius * self.scale,
+        }
+    }
+
+    /// Actividad del cometa en su posición actual (0 si el cuerpo no es un cometa)
+    pub fn comet_activity(&self, position: Vec3, sun: Vec3) -> f32 {
+        match self.comet_tails {
+            Some(_) => comet::activity(position - sun, self.orbital_elements.perihelion()),
+            None => 0.0,
+        }
+    }
+
+    /// Billboards de la coma del cometa (vacío si el cuerpo no es un cometa activo)
+    pub fn coma_billboards(&self, position: Vec3, sun: Vec3, time: f32) -> Vec<Billboard> {
+        match &self.comet_tails {
+            Some(tails) => tails.coma(self.sphere_occluder(position, time).center, self.comet_activity(position, sun)),
+            None => Vec::new(),
+        }
+    }
+
+    /// Altura máxima del relieve en la geometría, en fracción del radio. Se limita por debajo
+    /// de la capa de nubes o de atmósfera más baja: ninguna cumbre las atraviesa y la
+    /// dispersión y las sombras, que tratan el cuerpo como una esfera, siguen siendo válidas.
+    pub fn relief(&self) -> f32 {
+        let displacement = self.terrain.map_or(0.0, |terrain| terrain.displacement);
+        let clouds = self.clouds.map_or(f32::INFINITY, |clouds| clouds.altitude);
+        let atmosphere = self.atmosphere.map_or(f32::INFINITY, |atmosphere| atmosphere.thickness);
+        displacement.min(clouds.min(atmosphere) * RELIEF_SHELL_MARGIN)
+    }
+
+    /// Atmósfera del cuerpo situada en el espacio del mundo, si tiene
+    pub fn atmosphere_shell(&self, position: Vec3, time: f32) -> Option<AtmosphereShell> {
+        self.atmosphere.map(|params| {
+            let sphere = self.sphere_occluder(position, time);
+            AtmosphereShell {
+                params,
+                planet_center: sphere.center,
+                planet_radius: sphere.radius,
+            }
+        })
+    }
+
+    /// Matriz de modelo de la capa atmosférica: el modelo escalado alrededor de su centro
+    pub fn atmosphere_model_matrix(&self, position: Vec3, time: f32) -> Mat4 {
+        let thickness = self.atmosphere.map_or(0.0, |atmosphere| atmosphere.thickness);
+        self.model_matrix(position, time)
+            * Mat4::new_translation(&self.bounds.center)
+            * Mat4::new_scaling(1.0 + thickness)
+            * Mat4::new_translation(&-self.bounds.center)
+    }
+
+    /// Matriz de modelo de la capa de nubes: misma inclinación que el cuerpo,
+    /// su propio giro y escalada alrededor del centro según la altitud de la capa
+    pub fn cloud_model_matrix(&self, position: Vec3, time: f32) -> Mat4 {
+        let Some(clouds) = self.clouds else {
+            return self.model_matrix(position, time);
+        };
+        let angle = spin_angle(self.rotation_phase, clouds.rotation_period, time);
+        self.spun_model_matrix(position, angle)
+            * Mat4::new_translation(&self.bounds.center)
+            * Mat4::new_scaling(1.0 + clouds.altitude)
+            * Mat4::new_translation(&-self.bounds.center)
+    }
+
+    /// Capa de nubes situada en el espacio del mundo, si tiene
+    pub fn cloud_shell(&self, position: Vec3, time: f32) -> Option<CloudShell> {
+        self.clouds.map(|layer| {
+            let sphere = self.sphere_occluder(position, time);
+            let model_matrix = self.cloud_model_matrix(position, time);
+            CloudShell {
+                layer,
+                center: sphere.center,
+                radius: sphere.radius * (1.0 + layer.altitude),
+                world_to_local: model_matrix.try_inverse().unwrap_or_else(Mat4::identity),
+                time,
+            }
+        })
+    }
+
+    /// Matriz de modelo de los anillos: en el plano ecuatorial del cuerpo (con su
+    /// inclinación), con la malla medida en radios del cuerpo.
+    /// Los anillos son simétricos, así que no hace falta aplicarles el giro propio.
+    pub fn ring_model_matrix(&self, position: Vec3) -> Mat4 {
+        let tilt = nalgebra_glm::rotation(self.axial_tilt, &Vec3::new(1.0, 0.0, 0.0));
+        crate::create_model_matrix(position, self.scale, self.rotation)
+            * Mat4::new_translation(&self.bounds.center)
+            * tilt
+            * Mat4::new_scaling(self.bounds.radius)
+    }
+
+    /// Anillo plano equivalente a los anillos del cuerpo, si tiene
+    pub fn ring_occluder(&self, position: Vec3, time: f32) -> Option<RingOccluder> {
+        let rings = self.rings.as_ref()?;
+        let model_matrix = self.ring_model_matrix(position);
+        let normal = model_matrix * Vec4::new(0.0, 1.0, 0.0, 0.0);
+        let radius = self.bounds.radius * self.scale;
+        Some(RingOccluder {
+            center: self.sphere_occluder(position, time).center,
+            normal: normal.xyz().normalize(),
+            inner_radius: rings.inner_radius * radius,
+            outer_radius: rings.outer_radius * radius,
+            opacity: rings.opacity,
+            profile: rings.profile.clone(),
+        })
+    }
+}
+
+/// Fracción de la capa más baja (nubes o atmósfera) que puede alcanzar el relieve del terreno
+const RELIEF_SHELL_MARGIN: f32 = 0.75;
+
+/// Color medio de los cuerpos sin material propio (la luna y el núcleo del cometa son grises)
+const IMPOSTOR_ROCK_COLOR: Color = Color::new(160, 160, 160);
+
+/// Ángulo de giro sobre el propio eje a partir de la fase inicial y el periodo
+fn spin_angle(phase: f32, period: f32, time: f32) -> f32 {
+    if period > 0.0 {
+        phase + 2.0 * std::f32::consts::PI * time / period
+    } else {
+        phase
+    }
 }
 
 #[derive(Clone, PartialEq)]
@@ -186,9 +464,6 @@ pub enum CelestialBodyEnum {
     Planet,
     GasGiant,
     Ringed,
-    Rings,
-    Planet2,
-    Mars,
     Moon,
     Comet,
 }
@@ -199,10 +474,7 @@ impl CelestialBodyEnum {
             CelestialBodyEnum::Star => CelestialType::Star,
             CelestialBodyEnum::Planet => CelestialType::Planet,
             CelestialBodyEnum::GasGiant => CelestialType::GasGiant,
-            CelestialBodyEnum::Ringed => CelestialType::Ringed,
-            CelestialBodyEnum::Rings => CelestialType::Rings,
-            CelestialBodyEnum::Planet2 => CelestialType::Planet2,
-            CelestialBodyEnum::Mars => CelestialType::Mars,
+            CelestialBodyEnum::Ringed => CelestialType::GasGiant,
             CelestialBodyEnum::Moon => CelestialType::Moon,
             CelestialBodyEnum::Comet => CelestialType::Comet,
         }
@@ -211,33 +483,32 @@ impl CelestialBodyEnum {
 
 pub struct BodyManager {
     pub all_bodies: Vec<CelestialBody>,
+    pub assets: AssetManager,         // Mallas a las que se refieren los cuerpos
     pub current_index: usize,
     pub zoom_level: f32,
 }
 
 impl BodyManager {
     pub fn new(
-        star_obj: Obj,
-        planet_obj: Obj,
-        gas_giant_obj: Obj,
-        ringed_obj: Obj,
-        rings_obj: Obj,
-        planet2_obj: Obj,
-        mars_obj: Obj,
-        moon_obj: Obj,
-        comet_obj: Obj,
+        mut assets: AssetManager,
+        sphere_mesh: MeshHandle,
         noise_star: Arc<FastNoiseLite>,
         noise_planet: Arc<FastNoiseLite>,
         noise_gas_giant: Arc<FastNoiseLite>,
         noise_moon: Arc<FastNoiseLite>,
         noise_comet: Arc<FastNoiseLite>,
     ) -> Self {
+        // Todos los cuerpos son la misma esfera escalada: se dibujan con una cadena de icoesferas
+        // compartida que se elige cada cuadro según su tamaño en pantalla (ver `update_lod`)
+        let bounds = assets.mesh(sphere_mesh).bounds;
+        let lods = LodChain::icosphere(&mut assets, &bounds, MAX_SUBDIVISIONS);
         BodyManager {
             all_bodies: vec![
                 // Estrella
                 CelestialBody::new(
                     "Star".to_string(),
-                    star_obj,
+                    lods.clone(),
+                    bounds,
                     CelestialType::Star,
                     OrbitalElements::new(
                         0.0, // semi_major_axis (estrella en el centro)
@@ -249,27 +520,22 @@ impl BodyManager {
                         0.0, // orbital_period (no orbita)
                     ),
                     1.0, // scale
-                    Vec3::new(0.0, 0.0, 0.0), // rotation
                     noise_star.clone(),
-                    1.0,        // noise_scale
-                    -0.6,       // ocean_threshold
-                    0.65,       // continent_threshold
-                    0.1,        // mountain_threshold
-                    0.0,        // snow_threshold
-                    0.0,        // ring_inner_radius
-                    0.0,        // ring_outer_radius
-                    Color::black(), // ring_color
-                    0.0,        // ring_opacity
-                    0.0,        // ring_frequency
-                    0.0,        // ring_wave_speed
-                    Mat4::identity(), // ring_rotation_matrix
-                    false,      // is_moon
-                    "".to_string(), // orbiting_body_name
-                ),
+                )
+                .with_thresholds(TerrainThresholds::new(
+                    1.0, // noise_scale
+                    -0.6, // ocean
+                    0.65, // continent
+                    0.1, // mountain
+                    0.0, // snow
+                ))
+                .with_rotation(7.25 * std::f32::consts::PI / 180.0, 25.0, 0.0)
+                .with_solar(SolarMaterial::sun_like()),
                 // Planeta
                 CelestialBody::new(
                     "Planet".to_string(),
-                    planet_obj,
+                    lods.clone(),
+                    bounds,
                     CelestialType::Planet,
                     OrbitalElements::new(
                         5.0, // semi_major_axis
@@ -281,27 +547,24 @@ impl BodyManager {
                         30.0, // orbital_period (segundos)
                     ),
                     0.3, // scale
-                    Vec3::new(0.0, 0.0, 0.0), // rotation
                     noise_planet.clone(),
+                )
+                .with_thresholds(TerrainThresholds::new(
                     3.0, // noise_scale
-                    -0.038, // ocean_threshold
-                    0.85, // continent_threshold
-                    0.2, // mountain_threshold
-                    0.05, // snow_threshold
-                    0.0, // ring_inner_radius
-                    0.0, // ring_outer_radius
-                    Color::black(), // ring_color
-                    0.0, // ring_opacity
-                    0.0, // ring_frequency
-                    0.0, // ring_wave_speed
-                    Mat4::identity(), // ring_rotation_matrix
-                    false,      // is_moon
-                    "".to_string(), // orbiting_body_name
-                ),
+                    -0.038, // ocean
+                    0.85, // continent
+                    0.2, // mountain
+                    0.05, // snow
+                ))
+                .with_rotation(23.4 * std::f32::consts::PI / 180.0, 7.85, 0.0)
+                .with_terrain(TerrainMaterial::earth_like())
+                .with_clouds(CloudLayer::earth_like())
+                .with_atmosphere(Atmosphere::earth_like()),
                 // Gigante Gaseoso
                 CelestialBody::new(
                     "GasGiant".to_string(),
-                    gas_giant_obj,
+                    lods.clone(),
+                    bounds,
                     CelestialType::GasGiant,
                     OrbitalElements::new(
                         8.0, // semi_major_axis
@@ -313,28 +576,23 @@ impl BodyManager {
                         65.0, // orbital_period
                     ),
                     0.5, // scale
-                    Vec3::new(0.0, 0.0, 0.0), // rotation
                     noise_gas_giant.clone(),
+                )
+                .with_thresholds(TerrainThresholds::new(
                     15.0, // noise_scale
-                    -0.6, // ocean_threshold
-                    0.65, // continent_threshold
-                    0.1, // mountain_threshold
-                    0.0, // snow_threshold
-                    0.0, // ring_inner_radius
-                    0.0, // ring_outer_radius
-                    Color::black(), // ring_color
-                    0.0, // ring_opacity
-                    0.0, // ring_frequency
-                    0.0, // ring_wave_speed
-                    Mat4::identity(), // ring_rotation_matrix
-                    false,      // is_moon
-                    "".to_string(), // orbiting_body_name
-                ),
+                    -0.6, // ocean
+                    0.65, // continent
+                    0.1, // mountain
+                    0.0, // snow
+                ))
+                .with_rotation(3.1 * std::f32::consts::PI / 180.0, 4.0, 0.0)
+                .with_gas_giant(GasGiantMaterial::jupiter_like()),
                 // Planeta con Anillos
                 CelestialBody::new(
                     "Ringed".to_string(),
-                    ringed_obj,
-                    CelestialType::Ringed,
+                    lods.clone(),
+                    bounds,
+                    CelestialType::GasGiant,
                     OrbitalElements::new(
                         10.0, // semi_major_axis
                         0.02, // eccentricity
@@ -345,60 +603,35 @@ impl BodyManager {
                         35.0, // orbital_period
                     ),
                     0.5, // scale
-                    Vec3::new(0.0, 0.0, 0.0), // rotation
                     noise_gas_giant.clone(),
+                )
+                .with_thresholds(TerrainThresholds::new(
                     15.0, // noise_scale
-                    -0.6, // ocean_threshold
-                    0.65, // continent_threshold
-                    0.1, // mountain_threshold
-                    0.0, // snow_threshold
-                    0.0, // ring_inner_radius
-                    0.0, // ring_outer_radius
-                    Color::black(), // ring_color
-                    0.0, // ring_opacity
-                    0.0, // ring_frequency
-                    0.0, // ring_wave_speed
-                    Mat4::identity(), // ring_rotation_matrix
-                    false,      // is_moon
-                    "".to_string(), // orbiting_body_name
-                ),
-                // Anillos
-                CelestialBody::new(
-                    "Rings".to_string(),
-                    rings_obj,
-                    CelestialType::Rings,
-                    OrbitalElements::new(
-                        10.0, // Same semi_major_axis as Ringed
-                        0.02, // Same eccentricity
-                        15.0 * std::f32::consts::PI / 180.0, // Same inclinación
-                        0.0,
-                        0.0,
-                        0.0,
-                        35.0, // orbital_period
-                    ),
-                    0.4, // scale (ajusta según el modelo de anillos)
-                    Vec3::new(45.0, 0.0, 25.0), // rotation (ajusta para la inclinación de los anillos)
-                    noise_gas_giant.clone(),
-                    0.0, // noise_scale (no usado para anillos)
-                    0.0, // ocean_threshold
-                    0.0, // continent_threshold
-                    0.0, // mountain_threshold
-                    0.0, // snow_threshold
-                    1.0, // ring_inner_radius
-                    4.0, // ring_outer_radius
-                    Color::new(200, 200, 200), // ring_color
-                    0.7, // ring_opacity
-                    15.0, // ring_frequency
-                    0.5, // ring_wave_speed
-                    nalgebra_glm::rotation(45.0_f32.to_radians(), &Vec3::new(1.0, 0.0, 0.0)), // ring_rotation_matrix
-                    false,      // is_moon
-                    "".to_string(), // orbiting_body_name
-                ),
+                    -0.6, // ocean
+                    0.65, // continent
+                    0.1, // mountain
+                    0.0, // snow
+                ))
+                .with_rings(
+                    RingSystem {
+                        inner_radius: 1.25, // En radios del planeta
+                        outer_radius: 2.35,
+                        color: Color::new(214, 200, 176),
+                        opacity: 0.7,
+                        frequency: 60.0, // Ondas de densidad por unidad de radio
+                        wave_speed: 0.3,
+                        profile: RingProfile::saturn_like(),
+                    },
+                    &mut assets,
+                )
+                .with_rotation(26.7 * std::f32::consts::PI / 180.0, 4.5, 0.0)
+                .with_gas_giant(GasGiantMaterial::saturn_like()),
                 // Planet2
                 CelestialBody::new(
                     "Planet2".to_string(),
-                    planet2_obj,
-                    CelestialType::Planet2,
+                    lods.clone(),
+                    bounds,
+                    CelestialType::Planet,
                     OrbitalElements::new(
                         6.0, // semi_major_axis
                         0.05, // eccentricity
@@ -409,28 +642,25 @@ impl BodyManager {
                         40.0, // orbital_period
                     ),
                     0.3, // scale
-                    Vec3::new(0.0, 0.0, 0.0), // rotation
                     noise_planet.clone(),
+                )
+                .with_thresholds(TerrainThresholds::new(
                     3.0, // noise_scale
-                    -0.038, // ocean_threshold
-                    0.85, // continent_threshold
-                    0.2, // mountain_threshold
-                    0.05, // snow_threshold
-                    0.0, // ring_inner_radius
-                    0.0, // ring_outer_radius
-                    Color::black(), // ring_color
-                    0.0, // ring_opacity
-                    0.0, // ring_frequency
-                    0.0, // ring_wave_speed
-                    Mat4::identity(), // ring_rotation_matrix
-                    false,      // is_moon
-                    "".to_string(), // orbiting_body_name
-                ),
+                    -0.038, // ocean
+                    0.85, // continent
+                    0.2, // mountain
+                    0.05, // snow
+                ))
+                .with_rotation(12.0 * std::f32::consts::PI / 180.0, 7.85, 1.0)
+                .with_terrain(TerrainMaterial::ocean_world())
+                .with_clouds(CloudLayer::greenish())
+                .with_atmosphere(Atmosphere::teal()),
                 // Marte
                 CelestialBody::new(
                     "Mars".to_string(),
-                    mars_obj,
-                    CelestialType::Mars,
+                    lods.clone(),
+                    bounds,
+                    CelestialType::Planet,
                     OrbitalElements::new(
                         4.0, // semi_major_axis
                         0.08, // eccentricity
@@ -441,27 +671,24 @@ impl BodyManager {
                         20.0, // orbital_period
                     ),
                     0.2, // scale
-                    Vec3::new(0.0, 0.0, 0.0), // rotation
                     noise_planet.clone(),
+                )
+                .with_thresholds(TerrainThresholds::new(
                     6.0, // noise_scale
-                    -0.038, // ocean_threshold
-                    0.85, // continent_threshold
-                    0.2, // mountain_threshold
-                    0.05, // snow_threshold
-                    0.0, // ring_inner_radius
-                    0.0, // ring_outer_radius
-                    Color::black(), // ring_color
-                    0.0, // ring_opacity
-                    0.0, // ring_frequency
-                    0.0, // ring_wave_speed
-                    Mat4::identity(), // ring_rotation_matrix
-                    false,      // is_moon
-                    "".to_string(), // orbiting_body_name
-                ),
+                    -0.038, // ocean
+                    0.85, // continent
+                    0.2, // mountain
+                    0.05, // snow
+                ))
+                .with_rotation(25.2 * std::f32::consts::PI / 180.0, 8.0, 2.0)
+                .with_terrain(TerrainMaterial::mars_like().with_displacement(0.025))
+                .with_clouds(CloudLayer::dust_storms())
+                .with_atmosphere(Atmosphere::dusty()),
                 // Luna
                 CelestialBody::new(
                     "Moon".to_string(),
-                    moon_obj,
+                    lods.clone(),
+                    bounds,
                     CelestialType::Moon,
                     OrbitalElements::new(
                         0.7, // semi_major_axis
@@ -473,27 +700,23 @@ impl BodyManager {
                         7.0, // orbital_period
                     ),
                     0.06, // scale
-                    Vec3::new(0.0, 0.0, 0.0), // rotation
                     noise_moon.clone(),
+                )
+                .with_thresholds(TerrainThresholds::new(
                     2.0, // noise_scale
-                    -0.5, // ocean_threshold
-                    0.6, // continent_threshold
-                    0.2, // mountain_threshold
-                    0.0, // snow_threshold
-                    0.0, // ring_inner_radius
-                    0.0, // ring_outer_radius
-                    Color::black(), // ring_color
-                    0.0, // ring_opacity
-                    0.0, // ring_frequency
-                    0.0, // ring_wave_speed
-                    Mat4::identity(), // ring_rotation_matrix
-                    true,       // is_moon
-                    "Planet".to_string(), // orbiting_body_name
-                ),
+                    -0.5, // ocean
+                    0.6, // continent
+                    0.2, // mountain
+                    0.0, // snow
+                ))
+                .orbiting("Planet")
+                // Acoplamiento de marea: gira una vez por órbita
+                .with_rotation(1.5 * std::f32::consts::PI / 180.0, 7.0, std::f32::consts::PI),
                 // Cometa
                 CelestialBody::new(
                     "Comet".to_string(),
-                    comet_obj,
+                    lods.clone(),
+                    bounds,
                     CelestialType::Comet,
                     OrbitalElements::new(
                         12.0, // semi_major_axis
@@ -505,25 +728,20 @@ impl BodyManager {
                         100.0, // orbital_period
                     ),
                     0.1, // scale
-                    Vec3::new(0.0, 0.0, 0.0), // rotation
                     noise_comet.clone(),
+                )
+                .with_thresholds(TerrainThresholds::new(
                     7.0, // noise_scale
-                    -0.6, // ocean_threshold
-                    0.65, // continent_threshold
-                    0.1, // mountain_threshold
-                    0.0, // snow_threshold
-                    0.0, // ring_inner_radius
-                    0.0, // ring_outer_radius
-                    Color::black(), // ring_color
-                    0.0, // ring_opacity
-                    0.0, // ring_frequency
-                    0.0, // ring_wave_speed
-                    Mat4::identity(), // ring_rotation_matrix
-                    false,      // is_moon
-                    "".to_string(), // orbiting_body_name
-                ),
+                    -0.6, // ocean
+                    0.65, // continent
+                    0.1, // mountain
+                    0.0, // snow
+                ))
+                .with_rotation(40.0 * std::f32::consts::PI / 180.0, 3.0, 0.0)
+                .with_comet_tails(CometTails::halley_like()),
                 
             ],
+            assets,
             current_index: 0,
             zoom_level: 50.0,
         }
@@ -543,6 +761,82 @@ impl BodyManager {
         position
     }
 
+    /// Luz puntual emitida por la estrella en su posición actual
+    pub fn star_light(&self, time: f32) -> PointLight {
+        match self.all_bodies.iter().find(|body| body.shader_type == CelestialType::Star) {
+            Some(star) => PointLight::new(
+                self.get_body_position(star, time),
+                SUN_COLOR,
+                SUN_INTENSITY,
+                star.bounds.radius * star.scale,
+            ),
+            None => PointLight::new(Vec3::zeros(), SUN_COLOR, SUN_INTENSITY, 0.0),
+        }
+    }
+
+    /// Cuerpos que pueden proyectar sombra sobre `receiver` en el instante dado
+    pub fn shadow_casters(&self, receiver: &CelestialBody, time: f32) -> ShadowCasters {
+        let spheres = self
+            .all_bodies
+            .iter()
+            .filter(|body| body.name != receiver.name)
+            .filter(|body| body.shader_type != CelestialType::Star)
+            .map(|body| body.sphere_occluder(self.get_body_position(body, time), time))
+            .collect();
+
+        // Los anillos de todos los cuerpos, también los del propio planeta, que lo sombrean
+        let rings = self
+            .all_bodies
+            .iter()
+            .filter_map(|body| body.ring_occluder(self.get_body_position(body, time), time))
+            .collect();
+
+        ShadowCasters { spheres, rings }
+    }
+
+    /// Cuerpos que pueden proyectar sombra sobre los anillos de `owner`: todos, incluido el
+    /// planeta que los lleva, y los anillos de los demás; los anillos no se sombrean a sí mismos
+    pub fn ring_shadow_casters(&self, owner: &CelestialBody, time: f32) -> ShadowCasters {
+        let spheres = self
+            .all_bodies
+            .iter()
+            .filter(|other| other.shader_type != CelestialType::Star)
+            .map(|other| other.sphere_occluder(self.get_body_position(other, time), time))
+            .collect();
+        let rings = self
+            .all_bodies
+            .iter()
+            .filter(|other| other.name != owner.name)
+            .filter_map(|other| other.ring_occluder(self.get_body_position(other, time), time))
+            .collect();
+
+        ShadowCasters { spheres, rings }
+    }
+
+    /// Elige el nivel de detalle de cada cuerpo según el radio que ocupa en pantalla visto
+    /// desde `eye`, con `pixels_per_unit` píxeles por unidad a distancia 1
+    pub fn update_lod(&mut self, eye: Vec3, pixels_per_unit: f32, time: f32) {
+        let spheres: Vec<SphereOccluder> = self
+            .all_bodies
+            .iter()
+            .map(|body| body.sphere_occluder(self.get_body_position(body, time), time))
+            .collect();
+        for (body, sphere) in self.all_bodies.iter_mut().zip(spheres) {
+            let distance = nalgebra_glm::distance(&eye, &sphere.center).max(1e-4);
+            body.lod = body.lods.select(body.lod, sphere.radius * pixels_per_unit / distance);
+        }
+    }
+
+    /// Malla con la que se dibuja `body` en su nivel de detalle actual
+    pub fn mesh(&self, body: &CelestialBody) -> &MeshAsset {
+        self.assets.mesh(body.current_mesh())
+    }
+
+    /// Corona con la que se dibujan los anillos de `body`, si tiene
+    pub fn ring_mesh(&self, body: &CelestialBody) -> Option<&MeshAsset> {
+        body.ring_mesh.map(|handle| self.assets.mesh(handle))
+    }
+
     pub fn get_body_by_name(&self, name: &str) -> Option<&CelestialBody> {
         self.all_bodies.iter().find(|body| body.name == name)
     }
@@ -565,3 +859,22 @@ impl BodyManager {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::create_body_manager;
+
+    #[test]
+    fn relief_is_opt_in_and_stays_below_the_lowest_shell() {
+        let body_manager = create_body_manager();
+        let planet = body_manager.get_body_by_name("Planet").unwrap();
+        assert_eq!(planet.relief(), 0.0);
+
+        let mars = body_manager.get_body_by_name("Mars").unwrap().clone();
+        assert!(mars.relief() > 0.0);
+        let steep = mars.with_terrain(TerrainMaterial::mars_like().with_displacement(0.5));
+        let clouds = steep.clouds.unwrap().altitude;
+        assert!(steep.relief() < clouds && steep.relief() < steep.atmosphere.unwrap().thickness);
+    }
+}
@@ -0,0 +1,379 @@
+// src/particles.rs
+
+use nalgebra_glm::{Vec3, Mat4};
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+use crate::billboard::{Billboard, render_billboards};
+use crate::color::Color;
+use crate::fragment::BlendMode;
+use crate::framebuffer::Framebuffer;
+
+/// Valor que cambia linealmente a lo largo de la vida de una partícula
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Curve {
+    pub start: f32,
+    pub end: f32,
+}
+
+impl Curve {
+    pub const fn new(start: f32, end: f32) -> Self {
+        Curve { start, end }
+    }
+
+    /// Valor en la fracción de vida `t` (0 = recién nacida, 1 = muere)
+    pub fn sample(&self, t: f32) -> f32 {
+        self.start + (self.end - self.start) * t.clamp(0.0, 1.0)
+    }
+}
+
+/// Color que cambia a lo largo de la vida de una partícula
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Gradient {
+    pub start: Color,
+    pub end: Color,
+}
+
+impl Gradient {
+    pub const fn new(start: Color, end: Color) -> Self {
+        Gradient { start, end }
+    }
+
+    pub fn sample(&self, t: f32) -> Color {
+        self.start.lerp(&self.end, t.clamp(0.0, 1.0))
+    }
+}
+
+/// Fuerza que actúa sobre todas las partículas de un emisor
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Force {
+    /// Aceleración radial con caída del inverso del cuadrado: positiva aleja del centro
+    /// (presión de radiación), negativa atrae
+    Radial { center: Vec3, strength: f32 },
+}
+
+impl Force {
+    fn acceleration(&self, position: &Vec3) -> Vec3 {
+        match self {
+            Force::Radial { center, strength } => {
+                let offset = position - center;
+                let distance_squared = offset.magnitude_squared().max(1e-4);
+                offset * (*strength / (distance_squared * distance_squared.sqrt()))
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Particle {
+    pub position: Vec3,
+    pub velocity: Vec3,
+    pub age: f32,
+    pub lifetime: f32,
+}
+
+impl Particle {
+    /// Fracción de vida consumida
+    pub fn life(&self) -> f32 {
+        self.age / self.lifetime
+    }
+}
+
+/// Fuente de partículas: las lanza en un cono alrededor de `direction`, las mueve con
+/// sus fuerzas y las dibuja como billboards con tamaño, color e intensidad según su edad.
+/// El dueño del emisor puede moverlo y cambiar sus parámetros en cada cuadro.
+#[derive(Clone, Debug)]
+pub struct Emitter {
+    pub position: Vec3,
+    pub velocity: Vec3,       // Velocidad del emisor; las partículas heredan `inherit_velocity` de ella
+    pub inherit_velocity: f32,
+    pub direction: Vec3,
+    pub spread: f32,          // Semiángulo del cono de emisión (radianes)
+    pub band: f32,            // Si es mayor que 0, las direcciones se sortean en una franja de este
+                              // semiancho (radianes) alrededor del ecuador de `direction`, no en el cono
+    pub shell_radius: f32,    // Si es mayor que 0, las partículas nacen sobre una esfera de este radio
+                              // (en la dirección sorteada del cono) en lugar de en el centro
+    pub velocity_spread: f32, // Con `shell_radius`, desvío de la velocidad respecto a la normal de la esfera
+    pub speed: Curve,         // Rapidez inicial: se elige al azar entre `start` y `end`
+    pub rate: f32,            // Partículas por segundo (0 = apagado)
+    pub lifetime: Curve,      // Vida en segundos: al azar entre `start` y `end`
+    pub size: Curve,          // Radio del billboard a lo largo de la vida
+    pub color: Gradient,
+    pub intensity: Curve,     // Brillo (aditivo) u opacidad (alfa) a lo largo de la vida
+    pub forces: Vec<Force>,
+    pub blend_mode: BlendMode,
+    pub max_particles: usize,
+    particles: Vec<Particle>,
+    spawn_accumulator: f32,
+}
+
+impl Emitter {
+    pub fn new(direction: Vec3, speed: Curve, rate: f32, lifetime: Curve, size: Curve, color: Gradient, intensity: Curve) -> Self {
+        Emitter {
+            position: Vec3::zeros(),
+            velocity: Vec3::zeros(),
+            inherit_velocity: 0.0,
+            direction,
+            spread: 0.0,
+            band: 0.0,
+            shell_radius: 0.0,
+            velocity_spread: 0.0,
+            speed,
+            rate,
+            lifetime,
+            size,
+            color,
+            intensity,
+            forces: Vec::new(),
+            blend_mode: BlendMode::Additive,
+            max_particles: 1024,
+            particles: Vec::new(),
+            spawn_accumulator: 0.0,
+        }
+    }
+
+    pub fn with_spread(mut self, spread: f32) -> Self {
+        self.spread = spread;
+        self
+    }
+
+    /// Lanza las partículas en una franja perpendicular a `direction` (el limbo de una estrella
+    /// visto desde esa dirección) en lugar de en un cono
+    pub fn with_band(mut self, band: f32) -> Self {
+        self.band = band;
+        self
+    }
+
+    /// Hace nacer las partículas sobre una esfera alrededor del emisor (superficie de una estrella)
+    pub fn with_shell(mut self, shell_radius: f32, velocity_spread: f32) -> Self {
+        self.shell_radius = shell_radius;
+        self.velocity_spread = velocity_spread;
+        self
+    }
+
+    pub fn with_inherited_velocity(mut self, inherit_velocity: f32) -> Self {
+        self.inherit_velocity = inherit_velocity;
+        self
+    }
+
+    pub fn with_force(mut self, force: Force) -> Self {
+        self.forces.push(force);
+        self
+    }
+
+    pub fn with_blend_mode(mut self, blend_mode: BlendMode) -> Self {
+        self.blend_mode = blend_mode;
+        self
+    }
+
+    /// Avanza la simulación `dt` segundos: envejece, mueve, elimina y lanza partículas
+    pub fn update<R: Rng>(&mut self, dt: f32, rng: &mut R) {
+        let forces = &self.forces;
+        for particle in self.particles.iter_mut() {
+            let acceleration: Vec3 = forces.iter().map(|force| force.acceleration(&particle.position)).sum();
+            particle.velocity += acceleration * dt;
+            particle.position += particle.velocity * dt;
+            particle.age += dt;
+        }
+        self.particles.retain(|particle| particle.age < particle.lifetime);
+
+        self.spawn_accumulator += self.rate.max(0.0) * dt;
+        while self.spawn_accumulator >= 1.0 {
+            self.spawn_accumulator -= 1.0;
+            if self.particles.len() >= self.max_particles {
+                continue;
+            }
+            let particle = self.spawn(rng);
+            self.particles.push(particle);
+        }
+    }
+
+    fn spawn<R: Rng>(&self, rng: &mut R) -> Particle {
+        let direction = if self.band > 0.0 {
+            band_sample(&self.direction, self.band, rng)
+        } else {
+            cone_sample(&self.direction, self.spread, rng)
+        };
+        let speed = rng.gen_range(self.speed.start.min(self.speed.end)..=self.speed.start.max(self.speed.end));
+        let lifetime = rng
+            .gen_range(self.lifetime.start.min(self.lifetime.end)..=self.lifetime.start.max(self.lifetime.end))
+            .max(1e-3);
+        let (position, direction) = if self.shell_radius > 0.0 {
+            (self.position + direction * self.shell_radius, cone_sample(&direction, self.velocity_spread, rng))
+        } else {
+            (self.position, direction)
+        };
+        Particle {
+            position,
+            velocity: direction * speed + self.velocity * self.inherit_velocity,
+            age: 0.0,
+            lifetime,
+        }
+    }
+
+    /// Billboards de las partículas vivas
+    pub fn billboards(&self) -> Vec<Billboard> {
+        self.particles
+            .iter()
+            .map(|particle| {
+                let life = particle.life();
+                Billboard::new(particle.position, self.size.sample(life), self.color.sample(life), self.intensity.sample(life))
+            })
+            .collect()
+    }
+}
+
+/// Identificador de un emisor dentro de su `ParticleSystem`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EmitterId(usize);
+
+/// Conjunto de emisores simulados en la CPU con un generador aleatorio reproducible
+pub struct ParticleSystem {
+    emitters: Vec<Emitter>,
+    rng: StdRng,
+}
+
+impl ParticleSystem {
+    pub fn new(seed: u64) -> Self {
+        ParticleSystem {
+            emitters: Vec::new(),
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    pub fn add_emitter(&mut self, emitter: Emitter) -> EmitterId {
+        self.emitters.push(emitter);
+        EmitterId(self.emitters.len() - 1)
+    }
+
+    pub fn emitter_mut(&mut self, id: EmitterId) -> &mut Emitter {
+        &mut self.emitters[id.0]
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        for emitter in self.emitters.iter_mut() {
+            emitter.update(dt, &mut self.rng);
+        }
+    }
+
+    /// Dibuja las partículas con prueba de profundidad y el modo de mezcla de cada emisor
+    pub fn render(&self, framebuffer: &mut Framebuffer, view_matrix: &Mat4, projection_matrix: &Mat4, viewport_matrix: &Mat4) {
+        for emitter in &self.emitters {
+            render_billboards(
+                framebuffer,
+                &emitter.billboards(),
+                emitter.blend_mode,
+                view_matrix,
+                projection_matrix,
+                viewport_matrix,
+            );
+        }
+    }
+}
+
+/// Dirección al azar dentro de un cono de semiángulo `spread` alrededor de `axis`
+fn cone_sample<R: Rng>(axis: &Vec3, spread: f32, rng: &mut R) -> Vec3 {
+    let axis = if axis.magnitude_squared() > 0.0 {
+        axis.normalize()
+    } else {
+        Vec3::new(0.0, 1.0, 0.0)
+    };
+    if spread <= 0.0 {
+        return axis;
+    }
+    // Muestreo uniforme del casquete esférico
+    let cos_theta = rng.gen_range(spread.min(std::f32::consts::PI).cos()..=1.0);
+    let sin_theta = (1.0 - cos_theta * cos_theta).max(0.0).sqrt();
+    let phi = rng.gen_range(0.0..2.0 * std::f32::consts::PI);
+    let (side, up) = perpendicular_basis(&axis);
+    axis * cos_theta + (side * phi.cos() + up * phi.sin()) * sin_theta
+}
+
+/// Dirección al azar, uniforme en área, a menos de `half_width` del círculo máximo perpendicular a `axis`
+fn band_sample<R: Rng>(axis: &Vec3, half_width: f32, rng: &mut R) -> Vec3 {
+    let axis = if axis.magnitude_squared() > 0.0 {
+        axis.normalize()
+    } else {
+        Vec3::new(0.0, 1.0, 0.0)
+    };
+    let max_sin = half_width.min(std::f32::consts::FRAC_PI_2).sin();
+    let sin_elevation = rng.gen_range(-max_sin..=max_sin);
+    let cos_elevation = (1.0 - sin_elevation * sin_elevation).max(0.0).sqrt();
+    let phi = rng.gen_range(0.0..2.0 * std::f32::consts::PI);
+    let (side, up) = perpendicular_basis(&axis);
+    axis * sin_elevation + (side * phi.cos() + up * phi.sin()) * cos_elevation
+}
+
+/// Dos vectores unitarios perpendiculares entre sí y a `direction`
+pub fn perpendicular_basis(direction: &Vec3) -> (Vec3, Vec3) {
+    let helper = if direction.y.abs() < 0.9 {
+        Vec3::new(0.0, 1.0, 0.0)
+    } else {
+        Vec3::new(1.0, 0.0, 0.0)
+    };
+    let side = direction.cross(&helper).normalize();
+    let up = side.cross(direction);
+    (side, up)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framebuffer::TransparencyMode;
+    use crate::{create_perspective_matrix, create_view_matrix, create_viewport_matrix};
+
+    #[test]
+    fn curve_interpolates_over_the_life_and_clamps() {
+        let curve = Curve::new(2.0, -1.0);
+        assert_eq!(curve.sample(0.0), 2.0);
+        assert_eq!(curve.sample(0.5), 0.5);
+        assert_eq!(curve.sample(1.0), -1.0);
+        assert_eq!(curve.sample(-0.5), 2.0);
+        assert_eq!(curve.sample(1.5), -1.0);
+    }
+
+    #[test]
+    fn gradient_interpolates_over_the_life_and_clamps() {
+        let gradient = Gradient::new(Color::new(200, 0, 100), Color::new(0, 200, 100));
+        assert_eq!(gradient.sample(0.0), Color::new(200, 0, 100));
+        assert_eq!(gradient.sample(0.5), Color::new(100, 100, 100));
+        assert_eq!(gradient.sample(2.0), Color::new(0, 200, 100));
+        assert_eq!(gradient.sample(-1.0), Color::new(200, 0, 100));
+    }
+
+    #[test]
+    fn particle_life_is_the_fraction_of_its_lifetime() {
+        let mut particle = Particle { position: Vec3::zeros(), velocity: Vec3::zeros(), age: 0.0, lifetime: 4.0 };
+        assert_eq!(particle.life(), 0.0);
+        particle.age = 1.0;
+        assert_eq!(particle.life(), 0.25);
+        particle.age = 4.0;
+        assert_eq!(particle.life(), 1.0);
+    }
+
+    #[test]
+    fn alpha_particles_show_up_after_the_oit_resolve() {
+        let mut system = ParticleSystem::new(1);
+        let white = Color::new(255, 255, 255);
+        let emitter = Emitter::new(
+            Vec3::new(0.0, 1.0, 0.0),
+            Curve::new(0.0, 0.0), // Quieta en el centro
+            10.0,
+            Curve::new(5.0, 5.0),
+            Curve::new(1.0, 1.0),
+            Gradient::new(white, white),
+            Curve::new(1.0, 1.0),
+        );
+        system.add_emitter(emitter.with_blend_mode(BlendMode::Alpha));
+        system.update(1.0);
+
+        let mut framebuffer = Framebuffer::new(32, 32);
+        framebuffer.set_transparency_mode(TransparencyMode::WeightedBlended);
+        framebuffer.clear();
+        framebuffer.resolve_transparency();
+        let view_matrix = create_view_matrix(Vec3::new(0.0, 0.0, 5.0), Vec3::zeros(), Vec3::new(0.0, 1.0, 0.0));
+        system.render(&mut framebuffer, &view_matrix, &create_perspective_matrix(32.0, 32.0), &create_viewport_matrix(32.0, 32.0));
+
+        // Nadie vuelve a resolver la OIT en este cuadro: la partícula tiene que estar ya en el color
+        assert!(Color::from_hex(framebuffer.buffer[16 * 32 + 16]).g > 128);
+    }
+}
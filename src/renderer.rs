@@ -1,6 +1,6 @@
 // src/renderer.rs
 
-use crate::fragment::{CelestialType};
+use crate::fragment::{CelestialType, BlendMode};
 use crate::framebuffer::Framebuffer;
 use crate::shaders::{fragment_shader, vertex_shader};
 use crate::triangle::triangle;
@@ -12,7 +12,7 @@ pub fn render(framebuffer: &mut Framebuffer, uniforms: &Uniforms, vertex_array:
     // Vertex Shader
     let mut transformed_vertices = Vec::with_capacity(vertex_array.len());
     for vertex in vertex_array {
-        let transformed = vertex_shader(vertex, uniforms);
+        let transformed = vertex_shader(vertex, uniforms, celestial_type);
         transformed_vertices.push(transformed);
     }
 
@@ -35,15 +35,20 @@ pub fn render(framebuffer: &mut Framebuffer, uniforms: &Uniforms, vertex_array:
     }
 
     // Procesamiento de Fragmentos
+    let blend_mode = celestial_type.blend_mode();
     for fragment in fragments {
         let x = fragment.position.x as usize;
         let y = fragment.position.y as usize;
 
         if x < framebuffer.width && y < framebuffer.height {
-            let (shaded_color, emissive) = fragment_shader(&fragment, &uniforms);
-            let color = shaded_color.to_hex();
-            framebuffer.set_current_color(color);
-            framebuffer.point(x, y, fragment.depth, emissive);
+            let shaded = fragment_shader(&fragment, &uniforms);
+            framebuffer.set_current_color(shaded.color.to_hex());
+            match blend_mode {
+                BlendMode::Opaque => framebuffer.point(x, y, fragment.depth, shaded.emission.to_hex()),
+                BlendMode::Additive => framebuffer.add_point(x, y, fragment.depth),
+                BlendMode::Alpha => framebuffer.blend_point(x, y, fragment.depth, shaded.alpha),
+                BlendMode::Emissive => framebuffer.add_emission(x, y, fragment.depth),
+            }
         }
     }
 }
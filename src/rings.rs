@@ -0,0 +1,91 @@
+// src/rings.rs
+
+use crate::color::Color;
+use crate::lighting::smoothstep;
+
+/// Ancho del borde suave entre bandas (fracción del ancho del sistema de anillos)
+const BAND_EDGE: f32 = 0.006;
+
+/// Banda de un sistema de anillos, con posiciones normalizadas entre el radio
+/// interior (0.0) y el exterior (1.0)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RingBand {
+    pub start: f32,
+    pub end: f32,
+    pub density: f32, // Profundidad óptica relativa (0 = hueco)
+    pub tint: Color,  // Se multiplica por el `ring_color` del cuerpo
+}
+
+impl RingBand {
+    pub const fn new(start: f32, end: f32, density: f32, tint: Color) -> Self {
+        RingBand {
+            start,
+            end,
+            density,
+            tint,
+        }
+    }
+}
+
+/// Perfil radial de densidad y color de un sistema de anillos; lo que queda
+/// entre bandas son huecos (divisiones)
+#[derive(Clone, Debug, PartialEq)]
+pub struct RingProfile {
+    pub bands: Vec<RingBand>,
+}
+
+impl RingProfile {
+    /// Anillos C, B y A con la división de Cassini, el hueco de Encke y un anillo F estrecho
+    pub fn saturn_like() -> Self {
+        RingProfile {
+            bands: vec![
+                RingBand::new(0.00, 0.22, 0.15, Color::new(170, 160, 150)), // Anillo C
+                RingBand::new(0.22, 0.58, 1.00, Color::new(255, 248, 235)), // Anillo B
+                RingBand::new(0.59, 0.63, 0.05, Color::new(140, 130, 120)), // División de Cassini
+                RingBand::new(0.63, 0.88, 0.55, Color::new(235, 225, 210)), // Anillo A
+                RingBand::new(0.895, 0.94, 0.45, Color::new(235, 225, 210)), // Anillo A, tras el hueco de Encke
+                RingBand::new(0.975, 0.99, 0.35, Color::new(245, 240, 230)), // Anillo F
+            ],
+        }
+    }
+
+    /// Densidad y tinte en una posición normalizada del anillo
+    pub fn sample(&self, t: f32) -> (f32, Color) {
+        let mut density = 0.0;
+        let mut tint = Color::black();
+        let mut weight = 0.0;
+
+        for band in &self.bands {
+            let coverage = smoothstep(band.start - BAND_EDGE, band.start + BAND_EDGE, t)
+                * (1.0 - smoothstep(band.end - BAND_EDGE, band.end + BAND_EDGE, t));
+            if coverage <= 0.0 {
+                continue;
+            }
+            density += band.density * coverage;
+            // El color de la banda dominante; en los bordes se mezclan
+            weight += coverage;
+            tint = tint.lerp(&band.tint, coverage / weight);
+        }
+
+        (density, tint)
+    }
+}
+
+/// Sistema de anillos de un cuerpo, con los radios medidos en radios del cuerpo
+#[derive(Clone, Debug, PartialEq)]
+pub struct RingSystem {
+    pub inner_radius: f32,
+    pub outer_radius: f32,
+    pub color: Color,
+    pub opacity: f32,
+    pub frequency: f32,   // Ondas de densidad por unidad de radio
+    pub wave_speed: f32,  // Rapidez con que las ondas se propagan hacia fuera
+    pub profile: RingProfile,
+}
+
+/// Opacidad de los anillos atravesados con un ángulo dado: cuanto más rasante
+/// es el rayo (coseno pequeño), más material cruza
+pub fn ring_alpha(density: f32, opacity: f32, cos_angle: f32) -> f32 {
+    let optical_depth = density * opacity * 2.0 / cos_angle.abs().max(0.05);
+    1.0 - (-optical_depth).exp()
+}
@@ -1,23 +1,44 @@
 use nalgebra_glm::{Vec3, Vec4, Mat3};
-use nalgebra::Point3; // Añadido para resolver el error E0433
 use crate::vertex::Vertex;
 use crate::Uniforms;
-use crate::fragment::{Fragment, CelestialType};
+use crate::fragment::{Fragment, FragmentColor, CelestialType};
 use crate::color::Color;
+use crate::lighting::{LightingParams, shade, shade_normal, smoothstep};
+use nalgebra_glm::mat4_to_mat3;
+use crate::material::{TerrainMaterial, GasGiantMaterial};
+use crate::rings::ring_alpha;
+use crate::atmosphere::henyey_greenstein;
+use crate::solar::SolarMaterial;
+use crate::texture::{SurfaceTextures, Texture};
+use std::sync::Arc;
+use std::sync::OnceLock;
 use nalgebra_glm::dot;
+
+/// Ancho del terminador para las luces de un mapa nocturno si el material no tiene ciudades
+const DEFAULT_TWILIGHT: f32 = 0.1;
+
 // Vertex Shader
-pub fn vertex_shader(vertex: &Vertex, uniforms: &Uniforms) -> Vertex {
+pub fn vertex_shader(vertex: &Vertex, uniforms: &Uniforms, celestial_type: CelestialType) -> Vertex {
+    // Relieve real del terreno: solo la superficie de los planetas rocosos, no sus capas
+    let (displaced_position, displaced_normal) = match celestial_type {
+        CelestialType::Planet if uniforms.appearance.relief > 0.0 => {
+            displace_terrain(&vertex.position, &vertex.normal, uniforms, uniforms.appearance.relief)
+        }
+        _ => (vertex.position, vertex.normal),
+    };
+
     let position = Vec4::new(
-        vertex.position.x,
-        vertex.position.y,
-        vertex.position.z,
+        displaced_position.x,
+        displaced_position.y,
+        displaced_position.z,
         1.0,
     );
 
-    let transformed = uniforms.projection_matrix
-        * uniforms.view_matrix
-        * uniforms.model_matrix
-        * position;
+    let world_position = uniforms.model_matrix * position;
+
+    let transformed = uniforms.view.projection_matrix
+        * uniforms.view.view_matrix
+        * world_position;
 
     let w = transformed.w;
     let transformed_position = Vec4::new(
@@ -27,7 +48,7 @@ pub fn vertex_shader(vertex: &Vertex, uniforms: &Uniforms) -> Vertex {
         1.0,
     );
 
-    let screen_position = uniforms.viewport_matrix * transformed_position;
+    let screen_position = uniforms.view.viewport_matrix * transformed_position;
 
     let model_mat3 = Mat3::new(
         uniforms.model_matrix[(0, 0)], uniforms.model_matrix[(0, 1)], uniforms.model_matrix[(0, 2)],
@@ -39,8 +60,9 @@ pub fn vertex_shader(vertex: &Vertex, uniforms: &Uniforms) -> Vertex {
         .unwrap_or(Mat3::identity())
         .transpose();
 
-    let transformed_normal = (normal_matrix * vertex.normal).normalize();
+    let transformed_normal = (normal_matrix * displaced_normal).normalize();
 
+    // `position` se queda sin desplazar: el color del terreno se sigue muestreando sobre la esfera
     Vertex {
         position: vertex.position,
         normal: vertex.normal,
@@ -52,392 +74,445 @@ pub fn vertex_shader(vertex: &Vertex, uniforms: &Uniforms) -> Vertex {
             screen_position.z,
         ),
         transformed_normal,
+        world_position: world_position.xyz(),
     }
 }
 
 
 // Fragment Shader Dispatcher
-pub fn fragment_shader(fragment: &Fragment, uniforms: &Uniforms) -> (Color, bool) {
+pub fn fragment_shader(fragment: &Fragment, uniforms: &Uniforms) -> FragmentColor {
     match fragment.celestial_type {
         CelestialType::Star => star_shader(fragment, uniforms),
-        CelestialType::Planet => rocky_planet_shader(fragment, uniforms),
+        CelestialType::Planet => terrain_shader(fragment, uniforms),
         CelestialType::GasGiant => gas_giant_shader(fragment, uniforms),
-        CelestialType::Ringed => ringed_shader(fragment, uniforms),
         CelestialType::Rings => rings_shader(fragment, uniforms),
-        CelestialType::Planet2 => rocky_planet_shader2(fragment, uniforms),
-        CelestialType::Mars => mars_shader(fragment, uniforms),
         CelestialType::Moon => moon_shader(fragment, uniforms),
         CelestialType::Comet => comet_shader(fragment, uniforms),
+        CelestialType::Atmosphere => atmosphere_shader(fragment, uniforms),
+        CelestialType::Clouds => cloud_shader(fragment, uniforms),
 
     }
 }
 
 
-// Shader para Estrella
-fn star_shader(fragment: &Fragment, uniforms: &Uniforms) -> (Color, bool) {
-    let noise_scale = 150.0;
-
-    // Obtenemos el valor del ruido en 3D para la posición del fragmento
-    let noise_value = uniforms.noise.get_noise_3d(
-        fragment.vertex_position.x * noise_scale,
-        fragment.vertex_position.y * noise_scale,
-        fragment.vertex_position.z * noise_scale,
-    );
+// Shader para Estrella: fotosfera con granulación celular que evoluciona con el tiempo,
+// manchas que derivan con la rotación diferencial y oscurecimiento del limbo. El color
+// sale de la temperatura local y el brillo de la ley de Stefan-Boltzmann (`SolarMaterial`)
+fn star_shader(fragment: &Fragment, uniforms: &Uniforms) -> FragmentColor {
+    static DEFAULT_MATERIAL: OnceLock<SolarMaterial> = OnceLock::new();
+    let material = uniforms
+        .appearance
+        .solar
+        .as_ref()
+        .unwrap_or_else(|| DEFAULT_MATERIAL.get_or_init(SolarMaterial::sun_like));
+    let time = uniforms.time;
+
+    let direction = (fragment.vertex_position.xyz() - uniforms.mesh_center).normalize();
+
+    // Granulación: dos patrones celulares consecutivos que se funden, así los gránulos
+    // nacen y se disuelven en lugar de deslizarse
+    let cycle = time / material.granulation_lifetime;
+    let generation = cycle.floor();
+    let granule = |generation: f32| {
+        let offset = Vec3::new(17.3, -9.1, 5.7) * generation;
+        let p = direction * material.granulation_scale + offset;
+        // Distancia al centro de la celda: pequeña dentro del gránulo, grande en los surcos
+        let distance = uniforms.noise.get_noise_3d(p.x, p.y, p.z);
+        1.0 - smoothstep(-1.0, 0.2, distance)
+    };
+    let blend = smoothstep(0.0, 1.0, cycle.fract());
+    let granulation = granule(generation) * (1.0 - blend) + granule(generation + 1.0) * blend;
 
-    // Escalamos el valor de ruido para obtener un rango de 0 a 1
-    let normalized_noise = (noise_value + 1.0) * 0.5;
+    let temperature = (material.temperature + (granulation - 0.5) * 2.0 * material.granulation_contrast)
+        * material.spot_temperature(&direction, time);
 
-    // Definimos colores base para simular la apariencia de una estrella
-    let base_red = Color::new(253, 46, 4);       // Naranja-rojo
-    let hot_yellow = Color::new(248, 96, 13);  // Amarillo claro
+    // Oscurecimiento del limbo: cerca del borde se ven capas más altas y frías
+    let view_dir = (uniforms.lighting.camera_position - fragment.world_position).normalize();
+    let limb = material.limb_darkening(dot(&fragment.normal, &view_dir));
 
-    // Interpolamos entre los colores según el valor de ruido
-    let star_color = base_red.lerp(&hot_yellow, normalized_noise);
+    let intensity = (temperature / material.temperature).powi(4) * limb * material.brightness;
+    let star_color = Color::from_temperature(temperature * limb.powf(0.25)) * intensity;
 
-    // La emisividad será directamente el color generado
-    (star_color, true) // `true` indica que es emisivo
+    // La emisión será directamente el color generado
+    FragmentColor::opaque(star_color).with_emission(star_color)
 }
 
-// Shader para Planeta Rocoso
-pub fn rocky_planet_shader(fragment: &Fragment, uniforms: &Uniforms) -> (Color, bool) {
-    // Colores base para los diferentes tipos de terreno
-    let ocean_color = Color::new(10, 115, 252);        // Azul para océano
-    let continent_color = Color::new(34, 139, 34);    // Verde para tierra/continente
-    let snow_color = Color::new(255, 250, 250); // Blanco nieve
-
-    let mountain_color = Color::new(97, 77, 63);     // Marrón para montañas
-    let cloud_color = Color::new(255, 255, 255);      // Blanco para nubes
-    let atmosphere_color = Color::new(173, 216, 230); // Azul claro para atmósfera
+/// Material de terreno evaluado en un punto del espacio del modelo, antes de iluminar.
+/// Es lo que `bake` guarda en texturas para no recalcular el ruido en cada fragmento.
+pub struct TerrainSurface {
+    pub height: f32,        // Valor del ruido del terreno
+    pub albedo: Color,
+    pub specular: f32,
+    pub city_lights: Color, // Luces de ciudades a plena noche, sin el brillo del material
+}
 
-    
+/// Clasifica el terreno en un punto (océano, continente, montaña y nieve) con el ruido del cuerpo
+pub fn terrain_surface(uniforms: &Uniforms, material: &TerrainMaterial, position: &Vec3) -> TerrainSurface {
     // Parámetros de escala y umbrales de ruido para el terreno
-    let noise_scale = uniforms.noise_scale;
-    let ocean_threshold = uniforms.ocean_threshold;
-    let mountain_threshold = uniforms.mountain_threshold;
-    let continent_threshold = uniforms.continent_threshold;
-    let snow_threshold = uniforms.snow_threshold; // Nuevo umbral para nieve
-
-    // Simulación de rotación y cálculo de ruido
-    let rotation_speed = 0.8; // Ajusta según sea necesario
-    let angle = uniforms.time * rotation_speed;
-    let rotated_x = fragment.vertex_position.x * angle.cos() - fragment.vertex_position.z * angle.sin();
-    let rotated_z = fragment.vertex_position.x * angle.sin() + fragment.vertex_position.z * angle.cos();
-    let rotated_position = nalgebra_glm::Vec3::new(rotated_x, fragment.vertex_position.y, rotated_z);
+    let thresholds = &uniforms.appearance.thresholds;
+    let noise_scale = thresholds.noise_scale;
+    let ocean_threshold = thresholds.ocean;
+    let mountain_threshold = thresholds.mountain;
+    let continent_threshold = thresholds.continent;
+    let snow_threshold = thresholds.snow; // Nuevo umbral para nieve
 
     // Generar ruido para definir el tipo de terreno usando ruido 3D
     let terrain_noise_value = uniforms.noise.get_noise_3d(
-        rotated_position.x * noise_scale,
-        rotated_position.y * noise_scale,
-        rotated_position.z * noise_scale,
+        position.x * noise_scale,
+        position.y * noise_scale,
+        position.z * noise_scale,
     );
 
     // Clasificar las zonas usando los umbrales para definir océano, continente y montaña
-    let mut surface_color = if terrain_noise_value < ocean_threshold {
-        ocean_color
+    let surface_color = if terrain_noise_value < ocean_threshold {
+        material.ocean_color
     } else if terrain_noise_value < mountain_threshold {
-        mountain_color
+        material.mountain_color
     } else if terrain_noise_value < continent_threshold {
-        continent_color
+        material.continent_color
     } else {
-        mountain_color.blend_subtract(&Color::new(50, 50, 50))
+        material.mountain_color.blend_subtract(&Color::new(50, 50, 50))
     };
 
     // Añadir capa de nieve en regiones de alta altitud
-    if terrain_noise_value > snow_threshold {
+    let snow_factor = if terrain_noise_value > snow_threshold {
         // Calcular la cantidad de nieve basada en la altitud
-        let snow_factor = ((terrain_noise_value - snow_threshold) / (1.0 - snow_threshold)).clamp(0.0, 1.0);
-        
+        ((terrain_noise_value - snow_threshold) / (1.0 - snow_threshold)).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+
+    // Brillo especular solo en océanos y hielo
+    let specular = if terrain_noise_value < ocean_threshold { material.ocean_specular } else { snow_factor * 0.3 };
+
+    // Luces de ciudades: solo en tierra firme
+    let city_lights = match material.city_lights {
+        Some(city_lights) if terrain_noise_value >= ocean_threshold => {
+            // Cúmulos grandes moteados con ruido fino para que no sean manchas uniformes
+            let clusters = uniforms.noise.get_noise_3d(
+                position.x * city_lights.scale,
+                position.y * city_lights.scale,
+                position.z * city_lights.scale,
+            );
+            let speckle = uniforms.noise.get_noise_3d(
+                position.x * city_lights.scale * 4.0,
+                position.y * city_lights.scale * 4.0,
+                position.z * city_lights.scale * 4.0,
+            );
+            let density = smoothstep(city_lights.threshold, city_lights.threshold + 0.2, clusters)
+                * smoothstep(-0.1, 0.5, speckle);
+
+            // No hay ciudades sobre el hielo
+            city_lights.color * (density * (1.0 - snow_factor))
+        }
+        _ => Color::black(),
+    };
+
+    TerrainSurface {
+        height: terrain_noise_value,
         // Mezclar el color de la superficie con el color de la nieve
-        surface_color = surface_color.lerp(&snow_color, snow_factor);
+        albedo: surface_color.lerp(&material.snow_color, snow_factor),
+        specular,
+        city_lights,
     }
+}
 
-    // Parámetros para las nubes
-    let cloud_scale = 7.0;          // Escala de ruido para nubes
-    let cloud_speed = 0.3;          // Velocidad de movimiento de nubes
-    let cloud_threshold: f32 = 0.1; // Umbral para generar nubes
+// Shader para Planeta Rocoso: un único material de terreno parametrizado por la paleta
+// y las nubes de cada cuerpo (`TerrainMaterial`)
+pub fn terrain_shader(fragment: &Fragment, uniforms: &Uniforms) -> FragmentColor {
+    let material = uniforms.appearance.terrain.unwrap_or_else(TerrainMaterial::earth_like);
 
-    // Generar ruido 2D para las nubes
-    let cloud_noise_value = uniforms.noise.get_noise_2d(
-        rotated_position.x * cloud_scale + uniforms.time * cloud_speed,
-        rotated_position.y * cloud_scale + uniforms.time * cloud_speed,
-    );
+    // Posición fija a la superficie: el giro del planeta ya viene en la matriz de modelo
+    let surface_position = fragment.vertex_position.xyz();
 
-    // Calcular opacidad de las nubes (mapear de [-1,1] a [0,1])
-    let cloud_opacity = ((cloud_noise_value + 1.0) / 2.0).clamp(0.0, 1.0);
+    // **Texturas**: cada mapa presente se mezcla con su equivalente procedural. Si lo
+    // sustituyen por completo (como las horneadas por `bake`) no hace falta evaluar el ruido
+    let textures = uniforms.appearance.textures.as_ref();
+    let blend = textures.map_or(0.0, |textures| textures.blend);
+    let procedural = if textures.is_some_and(|textures| textures.replaces(&material)) {
+        None
+    } else {
+        Some(terrain_surface(uniforms, &material, &surface_position))
+    };
 
-    // Aplicar nubes sobre el terreno usando interpolación lineal (lerp)
-    if cloud_noise_value > cloud_threshold {
-        surface_color = surface_color.lerp(&cloud_color, cloud_opacity * 1.0); // Opacidad incrementada a 1.0
-    }
+    // El nivel de mipmap y los texels vecinos se resuelven una vez para todos los mapas
+    let point = textures.and_then(|textures| textures.locate(&fragment.tex_coords, fragment.uv_footprint));
+    let sample = |map: fn(&SurfaceTextures) -> Option<&Arc<Texture>>| {
+        textures.and_then(map).zip(point.as_ref()).map(|(texture, point)| texture.sample_at(point))
+    };
 
-    // **Iluminación Básica (Lambertiana)**
-    // Definir la dirección de la luz
-    let light_dir = uniforms.light_direction.normalize();
-    
-    // Calcular la intensidad de la luz basada en la normal del fragmento
-    let intensity = dot(&fragment.normal, &light_dir).max(0.0);
+    let mut surface_color = procedural.as_ref().map_or(Color::black(), |surface| surface.albedo);
+    if let Some(texel) = sample(|textures| textures.albedo.as_ref()) {
+        surface_color = surface_color.lerp(&Color::from_float(texel.x, texel.y, texel.z), blend);
+    }
 
-    // Aplicar la iluminación al color final
-    surface_color = surface_color * intensity;
+    // **Relieve**: normal perturbada con el gradiente del mismo ruido que define el terreno
+    let mut normal = match &procedural {
+        Some(surface) if material.bump_strength > 0.0 => {
+            terrain_bump_normal(fragment, uniforms, surface.height, material.bump_strength)
+        }
+        _ => fragment.normal,
+    };
+    if let Some(texel) = sample(|textures| textures.normal.as_ref()) {
+        let strength = textures.map_or(1.0, |textures| textures.normal_strength);
+        normal = normal.lerp(&mapped_normal(fragment, uniforms, &texel, strength), blend).normalize();
+    }
 
-    // **Atmósfera (Halo)**
-    let distance = rotated_position.magnitude();
-    
-    if distance > 1.0 && distance < 1.0 + 0.3 { // Grosor de atmósfera incrementado a 0.3
-        // Generar ruido para la atmósfera
-        let atmosphere_noise = uniforms.noise.get_noise_2d(
-            rotated_position.x * 20.0 + uniforms.time * 0.02,
-            rotated_position.y * 20.0 + uniforms.time * 0.02,
-        );
-
-        // Calcular opacidad de la atmósfera
-        let atmosphere_opacity: f32 = ((atmosphere_noise + 1.0) / 2.0).clamp(0.0, 1.0) * 0.7; // Opacidad incrementada a 0.7
-
-        // Mezclar el color de la atmósfera con el color actual
-        surface_color = surface_color.lerp(&atmosphere_color, atmosphere_opacity);
+    // **Iluminación**: las nubes (capa aparte) apagan el brillo especular a través de su sombra
+    let mut specular = procedural.as_ref().map_or(0.0, |surface| surface.specular);
+    if let Some(texel) = sample(|textures| textures.specular.as_ref()) {
+        specular += (texel.x - specular) * blend;
     }
+    let lighting = LightingParams::matte()
+        .with_specular(specular, 48.0)
+        .with_rim(0.35, 3.0, material.rim_color);
+    surface_color = shade_normal(surface_color, &normal, fragment, uniforms, &lighting);
+
+    // **Luces nocturnas**: solo en el lado de noche del terminador; las nubes las tapan al
+    // mezclarse encima
+    let (light_dir, _) = uniforms.lighting.light.incident(&fragment.world_position);
+    let sun_height = dot(&fragment.normal.normalize(), &light_dir);
+    let twilight = material.city_lights.map_or(DEFAULT_TWILIGHT, |city_lights| city_lights.twilight);
+    let night = 1.0 - smoothstep(-twilight, twilight * 0.5, sun_height);
+
+    let mut city_lights = procedural.map_or(Color::black(), |surface| surface.city_lights);
+    // En el lado de día no hace falta leer el mapa nocturno
+    if let Some(texel) = sample(|textures| textures.night.as_ref()).filter(|_| night > 0.0) {
+        city_lights = city_lights.lerp(&Color::from_float(texel.x, texel.y, texel.z), blend);
+    }
+    let intensity = material.city_lights.map_or(1.0, |city_lights| city_lights.intensity);
+    let emission = city_lights * (night * intensity);
 
-    // Devolver el color del terreno con las nubes y atmósfera, y marcarlo como no emisivo
-    (surface_color, false)
+    // Las nubes y la atmósfera se dibujan aparte (ver `cloud_shader` y `atmosphere_shader`)
+    // Devolver el color del terreno con las luces nocturnas como emisión
+    FragmentColor::opaque(surface_color).with_emission(emission)
 }
 
 
 
 
-// Shader para Gigante Gaseoso
-
-pub fn gas_giant_shader(fragment: &Fragment, uniforms: &Uniforms) -> (Color, bool) {
-    // Colores base para las bandas del gigante gaseoso
-    let band_color_1 = Color::new(200, 160, 100); // Beige claro
-    let band_color_2 = Color::new(150, 100, 50);  // Marrón rojizo
+/// Altura del terreno en un punto del espacio del modelo: el ruido del terreno,
+/// aplanado al nivel del mar para que los océanos queden lisos
+pub fn terrain_height(uniforms: &Uniforms, position: &Vec3) -> f32 {
+    let noise_scale = uniforms.appearance.thresholds.noise_scale;
+    uniforms
+        .noise
+        .get_noise_3d(position.x * noise_scale, position.y * noise_scale, position.z * noise_scale)
+        .max(uniforms.appearance.thresholds.ocean)
+}
 
-    // Color para las tormentas
-    let storm_color = Color::new(255, 69, 0);     // Rojo intenso para tormentas
+/// Gradiente de la altura del terreno en el espacio del modelo (diferencias finitas)
+fn terrain_gradient(uniforms: &Uniforms, position: &Vec3, height: f32) -> Vec3 {
+    const EPSILON: f32 = 0.01;
+    Vec3::new(
+        terrain_height(uniforms, &(position + Vec3::new(EPSILON, 0.0, 0.0))) - height,
+        terrain_height(uniforms, &(position + Vec3::new(0.0, EPSILON, 0.0))) - height,
+        terrain_height(uniforms, &(position + Vec3::new(0.0, 0.0, EPSILON))) - height,
+    ) / EPSILON
+}
 
-    // Parámetros para el movimiento sinusoidal de las bandas
-    let band_frequency = 7.0;
-    let wave_speed = 0.9;
-    let wave_amplitude = 0.5;
+/// Inclina una normal según la parte tangente de un gradiente de altura
+fn tilt_normal(normal: &Vec3, gradient: &Vec3, strength: f32) -> Vec3 {
+    let tangent_gradient = gradient - normal * dot(gradient, normal);
+    (normal - tangent_gradient * strength).normalize()
+}
 
-    // Crear las bandas usando un desplazamiento sinusoidal en función del tiempo
-    let latitude = fragment.vertex_position.y + (uniforms.time as f32 * wave_speed).sin() * wave_amplitude;
-    let band_factor = ((latitude * band_frequency).sin() * 0.5 + 0.5).clamp(0.0, 1.0);
-    let mut surface_color = band_color_1.lerp(&band_color_2, band_factor);
+/// Empuja un vértice hacia fuera según la altura del terreno sobre el nivel del mar, hasta
+/// `relief` veces su distancia al centro, y recalcula su normal con el gradiente de esa altura
+fn displace_terrain(position: &Vec3, normal: &Vec3, uniforms: &Uniforms, relief: f32) -> (Vec3, Vec3) {
+    let normal = normal.normalize();
+    let displacement = relief * (position - uniforms.mesh_center).magnitude();
+    let height = terrain_height(uniforms, position);
+    let ocean = uniforms.appearance.thresholds.ocean;
+    let elevation = (height - ocean) / (1.0 - ocean).max(1e-4);
 
-    // **Capa de tormentas independiente**
-    // Generamos ruido para la capa de tormentas. Esto se hace en una escala menor que las bandas
-    // para crear áreas focalizadas y localizadas en el planeta.
-    let storm_scale = 7.0; // Controla el tamaño de las tormentas
-    let storm_movement_speed = 0.7; // Velocidad de desplazamiento de las tormentas
+    let displaced = position + normal * (elevation * displacement);
+    let gradient = terrain_gradient(uniforms, position, height) / (1.0 - ocean).max(1e-4);
 
-    // Posición para el ruido de tormentas, usando una rotación lenta para que parezcan estables
-    let storm_noise_value = uniforms.noise.get_noise_2d(
-        fragment.vertex_position.x * storm_scale + uniforms.time * storm_movement_speed,
-        fragment.vertex_position.z * storm_scale + uniforms.time * storm_movement_speed,
-    );
+    (displaced, tilt_normal(&normal, &gradient, displacement))
+}
 
-    // Definir umbral para la intensidad de las tormentas
-    let storm_threshold = 0.5;
-    if storm_noise_value > storm_threshold {
-        let storm_intensity = (storm_noise_value - storm_threshold) / (1.0 - storm_threshold);
-        surface_color = surface_color.lerp(&storm_color, storm_intensity.clamp(0.0, 1.0));
+/// Normal del mundo a partir de un texel de un mapa de normales en espacio tangente. En la esfera la base
+/// tangente sale de la propia normal: el este es `Y × n` y el norte `n × este`.
+fn mapped_normal(fragment: &Fragment, uniforms: &Uniforms, texel: &Vec4, strength: f32) -> Vec3 {
+    // Los cuerpos tienen escala uniforme: la matriz del modelo lleva las normales igual que su
+    // inversa traspuesta, y así no hay que invertirla en cada fragmento
+    let model_mat3 = mat4_to_mat3(&uniforms.model_matrix);
+    let object_normal = (model_mat3.transpose() * fragment.normal).normalize();
+
+    // En los polos no hay este definido: se deja la normal de la esfera
+    let east = Vec3::new(0.0, 1.0, 0.0).cross(&object_normal);
+    if east.magnitude_squared() < 1e-8 {
+        return fragment.normal;
     }
+    let east = east.normalize();
+    let north = object_normal.cross(&east);
 
-    // **Iluminación Básica (Lambertiana)**
-    let light_dir = uniforms.light_direction.normalize();
-    let intensity = (fragment.normal.dot(&light_dir)).max(0.0);
-
-    // Aplicar iluminación al color de la superficie
-    surface_color = surface_color * intensity;
+    let tangent = texel.xyz() * 2.0 - Vec3::new(1.0, 1.0, 1.0);
+    let mapped = (east * tangent.x + north * tangent.y + object_normal * tangent.z).normalize();
 
-    // **Atmósfera Exterior (Halo)**
-    let distance_from_center = fragment.vertex_position.magnitude();
-    if distance_from_center > 1.0 && distance_from_center < 1.2 {
-        let atmosphere_noise = uniforms.noise.get_noise_2d(
-            fragment.vertex_position.x * 15.0 + uniforms.time * 0.05,
-            fragment.vertex_position.y * 15.0 + uniforms.time * 0.05,
-        );
-
-        let atmosphere_opacity = ((atmosphere_noise + 1.0) / 2.0).clamp(0.0, 0.6);
-        let atmosphere_color = Color::new(173, 216, 230); // Azul claro para el halo de atmósfera
+    (model_mat3 * object_normal.lerp(&mapped, strength)).normalize()
+}
 
-        // Mezclar atmósfera con el color de la superficie
-        surface_color = surface_color.lerp(&atmosphere_color, atmosphere_opacity);
-    }
+/// Normal del mundo inclinada según el gradiente de la altura del terreno (diferencias
+/// finitas en el espacio del modelo), para que montañas y cráteres tengan luz y sombra
+fn terrain_bump_normal(fragment: &Fragment, uniforms: &Uniforms, noise_value: f32, strength: f32) -> Vec3 {
+    // Se trabaja en el espacio del modelo, donde está definido el ruido
+    let model_mat3 = mat4_to_mat3(&uniforms.model_matrix);
+    let normal_matrix = model_mat3.try_inverse().unwrap_or(Mat3::identity()).transpose();
+    let object_normal = (model_mat3.transpose() * fragment.normal).normalize();
 
-    (surface_color, false)
+    let tilted = terrain_object_normal(uniforms, &fragment.vertex_position.xyz(), &object_normal, noise_value, strength);
+    (normal_matrix * tilted).normalize()
 }
 
+/// Normal del espacio del modelo en `position` inclinada por el relieve del terreno
+pub fn terrain_object_normal(uniforms: &Uniforms, position: &Vec3, object_normal: &Vec3, noise_value: f32, strength: f32) -> Vec3 {
+    let height = noise_value.max(uniforms.appearance.thresholds.ocean);
 
+    // Independiente de la escala del ruido
+    let gradient = terrain_gradient(uniforms, position, height) / uniforms.appearance.thresholds.noise_scale.max(1e-4);
+    tilt_normal(object_normal, &gradient, strength)
+}
 
-// Shader para Gigante Gaseoso con Patrones de Seno e Interpolación de Colores
 
+// Shader para Gigante Gaseoso: bandas de FBm deformadas (domain warping), rotación
+// diferencial por latitud y remolinos animados, con la paleta de cada cuerpo (`GasGiantMaterial`)
+pub fn gas_giant_shader(fragment: &Fragment, uniforms: &Uniforms) -> FragmentColor {
+    static DEFAULT_MATERIAL: OnceLock<GasGiantMaterial> = OnceLock::new();
+    let material = uniforms
+        .appearance
+        .gas_giant
+        .as_ref()
+        .unwrap_or_else(|| DEFAULT_MATERIAL.get_or_init(GasGiantMaterial::jupiter_like));
+    let time = uniforms.time;
+
+    // Coordenadas esféricas alrededor del centro de la malla
+    let direction = (fragment.vertex_position.xyz() - uniforms.mesh_center).normalize();
+    let mut latitude = direction.y.clamp(-1.0, 1.0).asin();
+    let mut longitude = direction.z.atan2(direction.x);
+
+    // **Tormentas**: se retuerce el punto de muestreo alrededor de cada remolino,
+    // así las bandas se enroscan en él
+    let mut storm_mask: f32 = 0.0;
+    let mut storm_color = Color::black();
+    for storm in &material.storms {
+        let center_longitude = storm.longitude + time * storm.drift_speed;
+        let east = wrap_angle(longitude - center_longitude) * storm.latitude.cos() / storm.aspect;
+        let north = latitude - storm.latitude;
+        let distance = (east * east + north * north).sqrt() / storm.radius;
+        if distance >= 1.0 {
+            continue;
+        }
+
+        let falloff = (1.0 - distance).powi(2);
+        let (sin, cos) = ((storm.swirl + time * storm.spin_speed) * falloff).sin_cos();
+        let swirled_east = east * cos - north * sin;
+        let swirled_north = east * sin + north * cos;
+        latitude = storm.latitude + swirled_north;
+        longitude = center_longitude + swirled_east * storm.aspect / storm.latitude.cos();
+
+        let core = 1.0 - smoothstep(0.35, 1.0, distance);
+        if core > storm_mask {
+            storm_mask = core;
+            storm_color = storm.color;
+        }
+    }
 
-// Shader para Anillos del Gigante Gaseoso
-pub fn ringed_shader(fragment: &Fragment, uniforms: &Uniforms) -> (Color, bool) {
-// Colores base para las bandas del gigante gaseoso
-let band_color_1 = Color::new(210, 180, 140); // Beige claro
-let band_color_2 = Color::new(191, 134, 103);   // Marrón oscuro
+    // **Rotación diferencial**: cada latitud deriva a su propia velocidad, con
+    // corrientes en chorro que alternan de sentido entre bandas
+    let flow = material.equator_speed * latitude.cos().powi(2)
+        + material.jet_speed * (latitude * material.band_frequency * 2.0).sin();
+    longitude -= time * flow;
+    let flowed = Vec3::new(
+        latitude.cos() * longitude.cos(),
+        latitude.sin(),
+        latitude.cos() * longitude.sin(),
+    );
 
-// Frecuencia de las bandas
-let band_frequency = 7.0;
+    // **Bandas turbulentas**: el FBm se muestrea en un dominio deformado por el propio ruido
+    let warp_position = flowed * material.warp_scale;
+    let warp = uniforms.noise.get_noise_3d(warp_position.x, warp_position.y, warp_position.z);
+    let warped_position = warp_position + Vec3::repeat(warp * material.warp_scale * 0.3);
+    let turbulence = uniforms.noise.get_noise_3d(warped_position.x, warped_position.y, warped_position.z);
 
-// Crear un patrón de bandas en función de la coordenada Y del planeta
-let band_factor = ((fragment.vertex_position.y * band_frequency).sin() * 0.7 + 0.5).clamp(0.0, 1.0);
+    let band_latitude = latitude + turbulence * material.turbulence;
+    let band_position = band_latitude / std::f32::consts::FRAC_PI_2 * material.band_frequency * material.bands.len() as f32;
+    let mut surface_color = material.band_color(band_position);
 
-// Interpolación entre los dos colores base
-let surface_color = band_color_1.lerp(&band_color_2, band_factor);
+    // Vetas finas estiradas a lo largo de las bandas
+    let detail_position = flowed * material.detail_scale;
+    let streak = uniforms.noise.get_noise_3d(detail_position.x, detail_position.y * 4.0, detail_position.z);
+    surface_color = surface_color * (1.0 + 0.15 * streak);
+    surface_color = surface_color.lerp(&storm_color, storm_mask * (0.8 + 0.2 * streak));
 
-// Iluminación básica (Lambertiana)
-let light_dir = uniforms.light_direction.normalize();
-let intensity = dot(&fragment.normal, &light_dir).max(0.0);
-let final_color = surface_color * intensity;
+    // **Iluminación**
+    let lighting = LightingParams::matte().with_rim(0.2, 4.0, material.rim_color);
+    surface_color = shade(surface_color, fragment, uniforms, &lighting);
 
-// Devolver el color final del planeta, sin emisividad
-(final_color, false)
+    FragmentColor::opaque(surface_color)
 }
-// shader para anillos 
-// Shader para los Anillos
-// Shader para los Anillos
-pub fn rings_shader(fragment: &Fragment, uniforms: &Uniforms) -> (Color, bool) {
-   // Convertir la posición a un punto 3D y luego a 2D para calcular la distancia en el plano XZ
-   let rotated_position = uniforms.ring_rotation_matrix.transform_point(&Point3::new(
-    fragment.vertex_position.x,
-    fragment.vertex_position.y,
-    fragment.vertex_position.z,
-));
-let distance = (rotated_position.x.powi(2) + rotated_position.z.powi(2)).sqrt();
-
-// Rango de los anillos
-let inner_radius = 1.1;
-let outer_radius = 5.0;
-
-if distance >= inner_radius && distance <= outer_radius {
-    // Generar un patrón de líneas radiales
-    let ring_frequency =50.0;
-    let ring_pattern = ((distance * ring_frequency).sin() * 0.5 + 0.5).clamp(0.0, 1.0);
-
-    // Colores base para los anillos
-    let ring_color_light = Color::new(220, 220, 220); // Gris claro
-    let ring_color_dark = Color::new(190, 207, 235);  // Gris oscuro
-
-    // Interpolación para alternar entre claro y oscuro
-    let ring_color = ring_color_light.lerp(&ring_color_dark, ring_pattern);
-
-    // Aplicar opacidad y devolver el color del anillo
-    let final_color = ring_color * uniforms.ring_opacity;
-    (final_color, false)
-} else {
-    // Si el fragmento está fuera del rango, se hace transparente
-    (Color::new(0, 0, 0), false)
-}
-}
-
-fn rocky_planet_shader2(fragment: &Fragment, uniforms: &Uniforms) -> (Color, bool) {
-    // Colores base para los diferentes tipos de terreno
-    let ocean_color = Color::new(65, 199, 240);        // Azul para océano
-    let continent_color = Color::new(
-        69, 128, 69
-        );    // Verde para tierra/continente
-    let snow_color = Color::new(219, 219, 200); // Blanco nieve
-
-    let mountain_color = Color::new(184, 121, 77);     // Marrón para montañas
-    let cloud_color = Color::new(139, 232, 190);      // Blanco para nubes
-    let atmosphere_color = Color::new(173, 216, 230); // Azul claro para atmósfera
 
-    
-    // Parámetros de escala y umbrales de ruido para el terreno
-    let noise_scale = uniforms.noise_scale;
-    let ocean_threshold = uniforms.ocean_threshold;
-    let mountain_threshold = uniforms.mountain_threshold;
-    let continent_threshold = uniforms.continent_threshold;
-    let snow_threshold = uniforms.snow_threshold; // Nuevo umbral para nieve
-
-    // Simulación de rotación y cálculo de ruido
-    let rotation_speed = 0.8; // Ajusta según sea necesario
-    let angle = uniforms.time * rotation_speed;
-    let rotated_x = fragment.vertex_position.x * angle.cos() - fragment.vertex_position.z * angle.sin();
-    let rotated_z = fragment.vertex_position.x * angle.sin() + fragment.vertex_position.z * angle.cos();
-    let rotated_position = nalgebra_glm::Vec3::new(rotated_x, fragment.vertex_position.y, rotated_z);
-
-    // Generar ruido para definir el tipo de terreno usando ruido 3D
-    let terrain_noise_value = uniforms.noise.get_noise_3d(
-        rotated_position.x * noise_scale,
-        rotated_position.y * noise_scale,
-        rotated_position.z * noise_scale,
-    );
+/// Lleva un ángulo al intervalo [-π, π]
+fn wrap_angle(angle: f32) -> f32 {
+    (angle + std::f32::consts::PI).rem_euclid(2.0 * std::f32::consts::PI) - std::f32::consts::PI
+}
 
-    // Clasificar las zonas usando los umbrales para definir océano, continente y montaña
-    let mut surface_color = if terrain_noise_value < ocean_threshold {
-        ocean_color
-    } else if terrain_noise_value < mountain_threshold {
-        mountain_color
-    } else if terrain_noise_value < continent_threshold {
-        continent_color
-    } else {
-        mountain_color.blend_subtract(&Color::new(50, 50, 50))
+// Shader para los Anillos: corona plana con un perfil radial de densidad y color
+// (bandas y divisiones), transparencia según la profundidad óptica y dispersión
+// hacia delante/atrás según el ángulo entre la luz y la cámara
+pub fn rings_shader(fragment: &Fragment, uniforms: &Uniforms) -> FragmentColor {
+    let Some(rings) = &uniforms.appearance.rings else {
+        return FragmentColor::transparent();
     };
-
-    // Añadir capa de nieve en regiones de alta altitud
-    if terrain_noise_value > snow_threshold {
-        // Calcular la cantidad de nieve basada en la altitud
-        let snow_factor = ((terrain_noise_value - snow_threshold) / (1.0 - snow_threshold)).clamp(0.0, 1.0);
-        
-        // Mezclar el color de la superficie con el color de la nieve
-        surface_color = surface_color.lerp(&snow_color, snow_factor);
-    }
-
-    // Parámetros para las nubes
-    let cloud_scale = 7.0;          // Escala de ruido para nubes
-    let cloud_speed = 0.3;          // Velocidad de movimiento de nubes
-    let cloud_threshold: f32 = 0.1; // Umbral para generar nubes
-
-    // Generar ruido 2D para las nubes
-    let cloud_noise_value = uniforms.noise.get_noise_2d(
-        rotated_position.x * cloud_scale + uniforms.time * cloud_speed,
-        rotated_position.y * cloud_scale + uniforms.time * cloud_speed,
-    );
-
-    // Calcular opacidad de las nubes (mapear de [-1,1] a [0,1])
-    let cloud_opacity = ((cloud_noise_value + 1.0) / 2.0).clamp(0.0, 1.0);
-
-    // Aplicar nubes sobre el terreno usando interpolación lineal (lerp)
-    if cloud_noise_value > cloud_threshold {
-        surface_color = surface_color.lerp(&cloud_color, cloud_opacity * 1.0); // Opacidad incrementada a 1.0
+    let radius = (fragment.vertex_position.x.powi(2) + fragment.vertex_position.z.powi(2)).sqrt();
+    let width = (rings.outer_radius - rings.inner_radius).max(1e-4);
+    let t = (radius - rings.inner_radius) / width;
+    if !(0.0..=1.0).contains(&t) {
+        return FragmentColor::transparent();
     }
 
-    // **Iluminación Básica (Lambertiana)**
-    // Definir la dirección de la luz
-    let light_dir = uniforms.light_direction.normalize();
-    
-    // Calcular la intensidad de la luz basada en la normal del fragmento
-    let intensity = dot(&fragment.normal, &light_dir).max(0.0);
-
-    // Aplicar la iluminación al color final
-    surface_color = surface_color * intensity;
+    let (mut density, tint) = rings.profile.sample(t);
 
-    // **Atmósfera (Halo)**
-    let distance = rotated_position.magnitude();
-    
-    if distance > 1.0 && distance < 1.0 + 0.3 { // Grosor de atmósfera incrementado a 0.3
-        // Generar ruido para la atmósfera
-        let atmosphere_noise = uniforms.noise.get_noise_2d(
-            rotated_position.x * 20.0 + uniforms.time * 0.02,
-            rotated_position.y * 20.0 + uniforms.time * 0.02,
-        );
-
-        // Calcular opacidad de la atmósfera
-        let atmosphere_opacity: f32 = ((atmosphere_noise + 1.0) / 2.0).clamp(0.0, 1.0) * 0.7; // Opacidad incrementada a 0.7
-
-        // Mezclar el color de la atmósfera con el color actual
-        surface_color = surface_color.lerp(&atmosphere_color, atmosphere_opacity);
+    // Ondas de densidad finas (ringlets) que se propagan lentamente hacia fuera
+    let wave = (radius * rings.frequency - uniforms.time * rings.wave_speed).sin() * 0.5 + 0.5;
+    density *= 0.75 + 0.25 * wave;
+    if density <= 0.0 {
+        return FragmentColor::transparent();
     }
 
-    // Devolver el color del terreno con las nubes y atmósfera, y marcarlo como no emisivo
-    (surface_color, false)
+    let normal = fragment.normal.normalize();
+    let light = &uniforms.lighting.light;
+    let (light_dir, _) = light.incident(&fragment.world_position);
+    let view_dir = (uniforms.lighting.camera_position - fragment.world_position).normalize();
+    let cos_light = dot(&normal, &light_dir);
+    let cos_view = dot(&normal, &view_dir);
+    let alpha = ring_alpha(density, rings.opacity, cos_view);
+
+    // Dispersión de las partículas: el hielo devuelve la luz hacia el sol y el polvo fino
+    // la deja pasar hacia delante cuando la cámara mira a contraluz
+    let scattering_cos = dot(&-light_dir, &view_dir);
+    let phase = 0.7 * henyey_greenstein(scattering_cos, -0.3) + 0.3 * henyey_greenstein(scattering_cos, 0.7);
+
+    // Capa fina de partículas (como la ley de Lommel-Seeliger): el brillo no cae hacia el
+    // borde como en una superficie sólida. La cara oscura solo recibe la luz que atraviesa
+    // las zonas finas.
+    let (mu_light, mu_view) = (cos_light.abs(), cos_view.abs());
+    let slab = 2.0 * mu_light / (mu_light + mu_view).max(1e-3);
+    let lit_face = cos_light * cos_view > 0.0;
+    let diffuse = if lit_face { slab } else { slab * (1.0 - alpha) };
+
+    let visibility = uniforms.lighting.shadows.visibility(&fragment.world_position, light);
+    let brightness = diffuse * phase * light.exposure_at(&fragment.world_position) * visibility;
+
+    let base = rings.color.blend_multiply(&tint);
+    let color = base * 0.05 + base.blend_multiply(&light.color) * brightness;
+
+    FragmentColor::opaque(color).with_alpha(alpha)
 }
+
 // Shader para Luna
-fn moon_shader(fragment: &Fragment, uniforms: &Uniforms) -> (Color, bool) {
+fn moon_shader(fragment: &Fragment, uniforms: &Uniforms) -> FragmentColor {
     // Definir colores
     let base_color = Color::new(169, 169, 169);       // Color gris oscuro de la superficie lunar
     let spot_color = Color::new(50, 50, 50);          // Color más oscuro para las manchas (cráteres o áreas irregulares)
@@ -463,118 +538,14 @@ fn moon_shader(fragment: &Fragment, uniforms: &Uniforms) -> (Color, bool) {
     // Mezclar colores usando el factor calculado
     let final_color = base_color.lerp(&spot_color, spot_intensity);
 
-    // Iluminación básica
-    let light_dir = uniforms.light_direction.normalize();
-    let light_intensity = dot(&fragment.normal, &light_dir).max(0.0);
-    let lit_color = final_color * light_intensity;
+    // Iluminación (superficie mate, sin atmósfera)
+    let lit_color = shade(final_color, fragment, uniforms, &LightingParams::matte());
 
-    (lit_color, false)
+    FragmentColor::opaque(lit_color)
 }
 
-fn mars_shader(fragment: &Fragment, uniforms: &Uniforms) -> (Color, bool) {
-    // Colores base para los diferentes tipos de terreno
-    let ocean_color = Color::new(125, 50, 41);        // Azul para océano
-    let continent_color = Color::new(176, 109, 67
-        );    // Verde para tierra/continente
-    let snow_color = Color::new(207, 174, 149); // Blanco nieve
-
-    let mountain_color = Color::new(128, 20, 13);     // Marrón para montañas
-    let cloud_color = Color::new(255, 193, 122);      // Blanco para nubes
-    let atmosphere_color = Color::new(229, 230, 209); // Azul claro para atmósfera
-
-    
-    // Parámetros de escala y umbrales de ruido para el terreno
-    let noise_scale = uniforms.noise_scale;
-    let ocean_threshold = uniforms.ocean_threshold;
-    let mountain_threshold = uniforms.mountain_threshold;
-    let continent_threshold = uniforms.continent_threshold;
-    let snow_threshold = uniforms.snow_threshold; // Nuevo umbral para nieve
-
-    // Simulación de rotación y cálculo de ruido
-    let rotation_speed = 0.8; // Ajusta según sea necesario
-    let angle = uniforms.time * rotation_speed;
-    let rotated_x = fragment.vertex_position.x * angle.cos() - fragment.vertex_position.z * angle.sin();
-    let rotated_z = fragment.vertex_position.x * angle.sin() + fragment.vertex_position.z * angle.cos();
-    let rotated_position = nalgebra_glm::Vec3::new(rotated_x, fragment.vertex_position.y, rotated_z);
-
-    // Generar ruido para definir el tipo de terreno usando ruido 3D
-    let terrain_noise_value = uniforms.noise.get_noise_3d(
-        rotated_position.x * noise_scale,
-        rotated_position.y * noise_scale,
-        rotated_position.z * noise_scale,
-    );
-
-    // Clasificar las zonas usando los umbrales para definir océano, continente y montaña
-    let mut surface_color = if terrain_noise_value < ocean_threshold {
-        ocean_color
-    } else if terrain_noise_value < mountain_threshold {
-        mountain_color
-    } else if terrain_noise_value < continent_threshold {
-        continent_color
-    } else {
-        mountain_color.blend_subtract(&Color::new(50, 50, 50))
-    };
-
-    // Añadir capa de nieve en regiones de alta altitud
-    if terrain_noise_value > snow_threshold {
-        // Calcular la cantidad de nieve basada en la altitud
-        let snow_factor = ((terrain_noise_value - snow_threshold) / (1.0 - snow_threshold)).clamp(0.0, 1.0);
-        
-        // Mezclar el color de la superficie con el color de la nieve
-        surface_color = surface_color.lerp(&snow_color, snow_factor);
-    }
-
-    // Parámetros para las nubes
-    let cloud_scale = 7.0;          // Escala de ruido para nubes
-    let cloud_speed = 0.3;          // Velocidad de movimiento de nubes
-    let cloud_threshold: f32 = 0.1; // Umbral para generar nubes
-
-    // Generar ruido 2D para las nubes
-    let cloud_noise_value = uniforms.noise.get_noise_2d(
-        rotated_position.x * cloud_scale + uniforms.time * cloud_speed,
-        rotated_position.y * cloud_scale + uniforms.time * cloud_speed,
-    );
-
-    // Calcular opacidad de las nubes (mapear de [-1,1] a [0,1])
-    let cloud_opacity = ((cloud_noise_value + 1.0) / 2.0).clamp(0.0, 1.0);
-
-    // Aplicar nubes sobre el terreno usando interpolación lineal (lerp)
-    if cloud_noise_value > cloud_threshold {
-        surface_color = surface_color.lerp(&cloud_color, cloud_opacity * 1.0); // Opacidad incrementada a 1.0
-    }
-
-    // **Iluminación Básica (Lambertiana)**
-    // Definir la dirección de la luz
-    let light_dir = uniforms.light_direction.normalize();
-    
-    // Calcular la intensidad de la luz basada en la normal del fragmento
-    let intensity = dot(&fragment.normal, &light_dir).max(0.0);
-
-    // Aplicar la iluminación al color final
-    surface_color = surface_color * intensity;
-
-    // **Atmósfera (Halo)**
-    let distance = rotated_position.magnitude();
-    
-    if distance > 1.0 && distance < 1.0 + 0.3 { // Grosor de atmósfera incrementado a 0.3
-        // Generar ruido para la atmósfera
-        let atmosphere_noise = uniforms.noise.get_noise_2d(
-            rotated_position.x * 20.0 + uniforms.time * 0.02,
-            rotated_position.y * 20.0 + uniforms.time * 0.02,
-        );
-
-        // Calcular opacidad de la atmósfera
-        let atmosphere_opacity: f32 = ((atmosphere_noise + 1.0) / 2.0).clamp(0.0, 1.0) * 0.7; // Opacidad incrementada a 0.7
-
-        // Mezclar el color de la atmósfera con el color actual
-        surface_color = surface_color.lerp(&atmosphere_color, atmosphere_opacity);
-    }
-
-    // Devolver el color del terreno con las nubes y atmósfera, y marcarlo como no emisivo
-    (surface_color, false)
-}
 // Shader para Cometa
-fn comet_shader(fragment: &Fragment, uniforms: &Uniforms) -> (Color, bool) {
+fn comet_shader(fragment: &Fragment, uniforms: &Uniforms) -> FragmentColor {
     // Definir colores para la roca del cometa y las áreas de sombra
     let base_color = Color::new(169, 169, 169);  // Gris para la base del cometa
     let dark_spot_color = Color::new(138, 135, 135); // Gris oscuro para cráteres y sombras
@@ -598,19 +569,58 @@ fn comet_shader(fragment: &Fragment, uniforms: &Uniforms) -> (Color, bool) {
         base_color.lerp(&dark_spot_color, normalized_noise) // Textura variable
     };
 
-    // Iluminación básica para simular la luz del sol
-    let light_dir = uniforms.light_direction.normalize();
-    let light_intensity = dot(&fragment.normal, &light_dir).max(0.0);
-
-    // Aplicar la iluminación a la textura rugosa
-    surface_color = surface_color * light_intensity;
+    // Iluminación para simular la luz del sol; el hielo del núcleo da un leve brillo
+    let lighting = LightingParams::matte().with_specular(0.2, 16.0);
+    surface_color = shade(surface_color, fragment, uniforms, &lighting);
 
     // Devolver el color con textura rocosa y sombreado, y marcarlo como no emisivo
-    (surface_color, false)
+    FragmentColor::opaque(surface_color)
 }
 
 
-// Shader para Nubes (si es necesario)
+// Shader para Nubes: capa esférica algo mayor que el planeta, con ruido 3D en su propio
+// espacio de modelo (gira con su matriz) y mezclada con la opacidad de cada nube
+fn cloud_shader(fragment: &Fragment, uniforms: &Uniforms) -> FragmentColor {
+    let Some(shell) = &uniforms.appearance.clouds else {
+        return FragmentColor::transparent();
+    };
+
+    // La cara trasera de la capa queda detrás del planeta
+    let view_dir = (fragment.world_position - uniforms.lighting.camera_position).normalize();
+    if dot(&fragment.normal, &view_dir) >= 0.0 {
+        return FragmentColor::transparent();
+    }
+
+    let density = shell.layer.density(&uniforms.noise, &fragment.vertex_position.xyz(), uniforms.time);
+    if density <= 0.0 {
+        return FragmentColor::transparent();
+    }
+
+    // Las nubes son blancas y mates: casi toda la luz que reciben es difusa
+    let lighting = LightingParams { ambient: 0.03, ..LightingParams::matte() };
+    let lit_color = shade(shell.layer.color, fragment, uniforms, &lighting);
 
-// Shader para Atmósfera
+    FragmentColor::opaque(lit_color).with_alpha(density)
+}
 
+// Shader para Atmósfera: capa algo mayor que el planeta con dispersión simple de Rayleigh/Mie.
+// Se suma sobre el framebuffer, así que el negro no aporta nada.
+fn atmosphere_shader(fragment: &Fragment, uniforms: &Uniforms) -> FragmentColor {
+    let Some(shell) = &uniforms.appearance.atmosphere else {
+        return FragmentColor::opaque(Color::black());
+    };
+
+    // Solo la cara frontal de la capa integra el rayo completo; la trasera se descarta
+    let view_dir = (fragment.world_position - uniforms.lighting.camera_position).normalize();
+    if dot(&fragment.normal, &view_dir) >= 0.0 {
+        return FragmentColor::opaque(Color::black());
+    }
+
+    let scattered = shell.scatter(&uniforms.lighting.camera_position, &view_dir, &uniforms.lighting.light);
+
+    // Los eclipses también apagan el cielo
+    let visibility = uniforms.lighting.shadows.visibility(&fragment.world_position, &uniforms.lighting.light);
+    let glow = scattered * visibility;
+
+    FragmentColor::opaque(Color::from_float(glow.x, glow.y, glow.z))
+}
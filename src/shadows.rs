@@ -0,0 +1,186 @@
+// src/shadows.rs
+
+use nalgebra_glm::{Vec3, dot};
+use std::f32::consts::PI;
+use crate::lighting::{PointLight, smoothstep};
+use crate::rings::{RingProfile, ring_alpha};
+
+/// Cuerpo esférico que puede tapar la luz de la estrella
+#[derive(Clone, Copy, Debug)]
+pub struct SphereOccluder {
+    pub center: Vec3,
+    pub radius: f32,
+}
+
+/// Sistema de anillos (anillo plano) que proyecta sombra
+#[derive(Clone, Debug)]
+pub struct RingOccluder {
+    pub center: Vec3,
+    pub normal: Vec3,
+    pub inner_radius: f32,
+    pub outer_radius: f32,
+    pub opacity: f32,
+    pub profile: RingProfile, // Las divisiones dejan pasar la luz
+}
+
+/// Todo lo que puede proyectar sombra sobre un cuerpo (sin incluirse a sí mismo)
+#[derive(Clone, Debug, Default)]
+pub struct ShadowCasters {
+    pub spheres: Vec<SphereOccluder>,
+    pub rings: Vec<RingOccluder>,
+}
+
+impl ShadowCasters {
+    /// Fracción de la luz de la estrella que llega al punto (1.0 = totalmente iluminado)
+    pub fn visibility(&self, point: &Vec3, light: &PointLight) -> f32 {
+        let to_light = light.position - point;
+        let light_distance = to_light.magnitude();
+        if light_distance <= light.radius {
+            return 1.0;
+        }
+        let light_dir = to_light / light_distance;
+        // Radio angular del disco de la estrella visto desde el punto: define la penumbra
+        let light_angle = (light.radius / light_distance).asin();
+
+        let mut visibility = 1.0;
+
+        for sphere in &self.spheres {
+            let to_occluder = sphere.center - point;
+            let occluder_distance = to_occluder.magnitude();
+
+            // Solo tapan la luz los cuerpos entre el punto y la estrella
+            if occluder_distance <= sphere.radius || occluder_distance >= light_distance {
+                continue;
+            }
+            let occluder_dir = to_occluder / occluder_distance;
+            if dot(&occluder_dir, &light_dir) <= 0.0 {
+                continue;
+            }
+
+            let occluder_angle = (sphere.radius / occluder_distance).asin();
+            let separation = dot(&occluder_dir, &light_dir).clamp(-1.0, 1.0).acos();
+            visibility *= 1.0 - disc_overlap(light_angle, occluder_angle, separation);
+        }
+
+        for ring in &self.rings {
+            visibility *= 1.0 - ring_shadow(ring, point, &light_dir, light_distance, light_angle);
+        }
+
+        visibility
+    }
+}
+
+/// Fracción del disco de la luz (radio angular `a`) cubierta por un disco de radio `b`
+/// cuyos centros están separados por `d` (geometría de dos círculos en el cielo del punto)
+fn disc_overlap(a: f32, b: f32, d: f32) -> f32 {
+    if d >= a + b {
+        return 0.0;
+    }
+    if d <= (a - b).abs() {
+        // Un disco contiene al otro: eclipse total o tránsito anular
+        return if b >= a { 1.0 } else { (b * b) / (a * a) };
+    }
+
+    let alpha = ((d * d + a * a - b * b) / (2.0 * d * a)).clamp(-1.0, 1.0).acos();
+    let beta = ((d * d + b * b - a * a) / (2.0 * d * b)).clamp(-1.0, 1.0).acos();
+    let kite = ((-d + a + b) * (d + a - b) * (d - a + b) * (d + a + b)).max(0.0).sqrt();
+    let area = a * a * alpha + b * b * beta - 0.5 * kite;
+
+    (area / (PI * a * a)).clamp(0.0, 1.0)
+}
+
+/// Opacidad de los anillos a lo largo del rayo hacia la estrella, con bordes suaves
+fn ring_shadow(ring: &RingOccluder, point: &Vec3, light_dir: &Vec3, light_distance: f32, light_angle: f32) -> f32 {
+    let denom = dot(light_dir, &ring.normal);
+    if denom.abs() < 1e-5 {
+        return 0.0;
+    }
+
+    let t = dot(&(ring.center - point), &ring.normal) / denom;
+    if t <= 1e-4 || t >= light_distance {
+        return 0.0;
+    }
+
+    let hit = point + light_dir * t;
+    let radius = (hit - ring.center).magnitude();
+
+    // Ancho de la penumbra en el plano del anillo según el tamaño aparente de la estrella
+    let penumbra = (t * light_angle.tan()).max(1e-4);
+    let inner = smoothstep(ring.inner_radius - penumbra, ring.inner_radius + penumbra, radius);
+    let outer = 1.0 - smoothstep(ring.outer_radius - penumbra, ring.outer_radius + penumbra, radius);
+
+    let (density, _) = ring.profile.sample((radius - ring.inner_radius) / (ring.outer_radius - ring.inner_radius));
+    ring_alpha(density, ring.opacity, denom) * inner * outer
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn disc_overlap_is_zero_for_separate_discs() {
+        assert_eq!(disc_overlap(0.1, 0.2, 0.3), 0.0);
+        assert_eq!(disc_overlap(0.1, 0.2, 0.5), 0.0);
+    }
+
+    #[test]
+    fn disc_overlap_is_total_when_the_occluder_covers_the_light() {
+        assert_eq!(disc_overlap(0.1, 0.3, 0.0), 1.0);
+        assert_eq!(disc_overlap(0.1, 0.3, 0.15), 1.0);
+    }
+
+    #[test]
+    fn disc_overlap_of_a_transit_is_the_area_ratio() {
+        assert!((disc_overlap(0.2, 0.05, 0.0) - 0.0625).abs() < 1e-6);
+        assert!((disc_overlap(0.2, 0.05, 0.1) - 0.0625).abs() < 1e-6);
+    }
+
+    #[test]
+    fn disc_overlap_grows_as_the_discs_approach() {
+        // Discos iguales con los centros a un radio: la lente cubre 2π/3 − √3/2 de π
+        let expected = (2.0 * PI / 3.0 - 3.0f32.sqrt() / 2.0) / PI;
+        assert!((disc_overlap(0.1, 0.1, 0.1) - expected).abs() < 1e-4);
+
+        let mut previous = 0.0;
+        for step in (0..20).rev() {
+            let overlap = disc_overlap(0.1, 0.08, step as f32 * 0.01);
+            assert!(overlap >= previous, "overlap {} at step {}", overlap, step);
+            previous = overlap;
+        }
+    }
+
+    #[test]
+    fn visibility_darkens_points_behind_an_occluder() {
+        let light = PointLight::new(Vec3::zeros(), crate::lighting::SUN_COLOR, 1.0, 1.0);
+        let casters = ShadowCasters {
+            spheres: vec![SphereOccluder { center: Vec3::new(10.0, 0.0, 0.0), radius: 1.0 }],
+            rings: Vec::new(),
+        };
+        assert_eq!(casters.visibility(&Vec3::new(20.0, 0.0, 0.0), &light), 0.0);
+        assert_eq!(casters.visibility(&Vec3::new(20.0, 5.0, 0.0), &light), 1.0);
+        // Un cuerpo detrás del punto no le quita luz
+        assert_eq!(casters.visibility(&Vec3::new(3.0, 0.0, 0.0), &light), 1.0);
+    }
+
+    #[test]
+    fn visibility_applies_every_ring_system() {
+        let light = PointLight::new(Vec3::zeros(), crate::lighting::SUN_COLOR, 1.0, 1.0);
+        let ring = |center: Vec3, normal: Vec3| RingOccluder {
+            center,
+            normal,
+            inner_radius: 1.0,
+            outer_radius: 4.0,
+            opacity: 1.0,
+            profile: RingProfile::saturn_like(),
+        };
+        // El primer sistema queda lejos del rayo; el segundo lo corta en su anillo B
+        let casters = ShadowCasters {
+            spheres: Vec::new(),
+            rings: vec![
+                ring(Vec3::new(100.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0)),
+                ring(Vec3::new(10.0, 0.0, 2.5), Vec3::new(1.0, 0.0, 0.0)),
+            ],
+        };
+        assert!(casters.visibility(&Vec3::new(20.0, 0.0, 0.0), &light) < 0.5);
+    }
+}
@@ -0,0 +1,246 @@
+// src/skybox.rs
+
+use nalgebra_glm::{Vec3, Vec4, Mat4};
+use fastnoise_lite::FastNoiseLite;
+use crate::color::Color;
+use crate::framebuffer::Framebuffer;
+use crate::lighting::smoothstep;
+
+/// Fondo de nebulosas y galaxia: nubes de colores según la densidad del ruido, una banda
+/// de la Vía Láctea alrededor del plano galáctico y franjas de polvo que la oscurecen
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NebulaMaterial {
+    pub noise_scale: f32,         // Escala de la dirección antes de muestrear el ruido
+    pub noise_range: [f32; 2],    // Valores del ruido que corresponden a densidad 0 y 1
+    pub cloud_threshold: f32,     // Densidad por debajo de la cual el cielo queda vacío
+    pub faint_color: Color,       // Gradiente de la nebulosa de menor a mayor densidad
+    pub mid_color: Color,
+    pub bright_color: Color,
+    pub nebula_intensity: f32,
+    pub galactic_pole: Vec3,      // Normal del plano de la Vía Láctea
+    pub band_width: f32,          // Semiancho angular de la banda (radianes)
+    pub band_color: Color,
+    pub band_intensity: f32,
+    pub dust_threshold: f32,      // Densidad a partir de la cual aparece el polvo
+    pub dust_opacity: f32,        // Cuánta luz de la banda tapan las franjas de polvo
+}
+
+impl NebulaMaterial {
+    /// Nubes violetas y azules tenues con una Vía Láctea cálida inclinada respecto a la eclíptica
+    pub fn milky_way() -> Self {
+        NebulaMaterial {
+            noise_scale: 30.0,
+            // El ruido celular es mínimo en el centro de cada celda: ahí la nube es más densa
+            noise_range: [-0.35, -0.95],
+            cloud_threshold: 0.5,
+            faint_color: Color::new(40, 10, 60),
+            mid_color: Color::new(120, 40, 110),
+            bright_color: Color::new(70, 140, 190),
+            nebula_intensity: 0.28,
+            galactic_pole: Vec3::new(0.3, 0.9, -0.3).normalize(),
+            band_width: 0.22,
+            band_color: Color::new(230, 215, 190),
+            band_intensity: 0.22,
+            dust_threshold: 0.6,
+            dust_opacity: 0.85,
+        }
+    }
+
+    /// Color del cielo en una dirección unitaria
+    pub fn sample(&self, noise: &FastNoiseLite, direction: &Vec3) -> Color {
+        let [empty, dense] = self.noise_range;
+        let density = |p: Vec3| ((noise.get_noise_3d(p.x, p.y, p.z) - empty) / (dense - empty)).clamp(0.0, 1.0);
+
+        let p = direction * self.noise_scale;
+        let cloud = density(p);
+        let amount = smoothstep(self.cloud_threshold, 1.0, cloud);
+        let nebula_color = if amount < 0.5 {
+            self.faint_color.lerp(&self.mid_color, amount * 2.0)
+        } else {
+            self.mid_color.lerp(&self.bright_color, amount * 2.0 - 1.0)
+        };
+        let nebula = nebula_color * (amount * self.nebula_intensity);
+
+        // Banda galáctica: gaussiana en la latitud galáctica, con grumos del mismo ruido
+        let latitude = direction.dot(&self.galactic_pole).clamp(-1.0, 1.0).asin();
+        let band = (-(latitude / self.band_width).powi(2)).exp() * (0.5 + 0.5 * cloud);
+
+        // Franjas de polvo: otro patrón más fino, solo dentro de la banda
+        let dust = smoothstep(self.dust_threshold, 1.0, density(p * 2.0 + Vec3::new(41.0, -17.0, 23.0)));
+        let transmittance = 1.0 - dust * self.dust_opacity * band.min(1.0);
+
+        let milky_way = self.band_color * (band * self.band_intensity);
+        (nebula + milky_way) * transmittance
+    }
+}
+
+/// Seis caras cuadradas en el orden +X, -X, +Y, -Y, +Z, -Z
+pub struct Cubemap {
+    pub resolution: usize,
+    faces: Vec<Vec<Color>>,
+}
+
+impl Cubemap {
+    /// Evalúa `texel` en la dirección del centro de cada texel de las seis caras
+    pub fn bake<F: Fn(&Vec3) -> Color>(resolution: usize, texel: F) -> Self {
+        let faces = (0..6)
+            .map(|face| {
+                let mut pixels = Vec::with_capacity(resolution * resolution);
+                for y in 0..resolution {
+                    for x in 0..resolution {
+                        let u = (x as f32 + 0.5) / resolution as f32 * 2.0 - 1.0;
+                        let v = (y as f32 + 0.5) / resolution as f32 * 2.0 - 1.0;
+                        pixels.push(texel(&face_direction(face, u, v).normalize()));
+                    }
+                }
+                pixels
+            })
+            .collect();
+        Cubemap { resolution, faces }
+    }
+
+    /// Color en una dirección (no hace falta que sea unitaria), con filtrado bilineal dentro de la cara
+    pub fn sample(&self, direction: &Vec3) -> Color {
+        let (face, u, v) = direction_to_face(direction);
+        let size = self.resolution as f32;
+        let x = ((u * 0.5 + 0.5) * size - 0.5).clamp(0.0, size - 1.0);
+        let y = ((v * 0.5 + 0.5) * size - 0.5).clamp(0.0, size - 1.0);
+        let (x0, y0) = (x as usize, y as usize);
+        let (x1, y1) = ((x0 + 1).min(self.resolution - 1), (y0 + 1).min(self.resolution - 1));
+        let (tx, ty) = (x - x0 as f32, y - y0 as f32);
+
+        let pixels = &self.faces[face];
+        let texel = |x: usize, y: usize| pixels[y * self.resolution + x];
+        let top = texel(x0, y0).lerp(&texel(x1, y0), tx);
+        let bottom = texel(x0, y1).lerp(&texel(x1, y1), tx);
+        top.lerp(&bottom, ty)
+    }
+}
+
+/// Dirección (sin normalizar) del punto (u, v) en [-1, 1] de una cara
+fn face_direction(face: usize, u: f32, v: f32) -> Vec3 {
+    match face {
+        0 => Vec3::new(1.0, -v, -u),
+        1 => Vec3::new(-1.0, -v, u),
+        2 => Vec3::new(u, 1.0, v),
+        3 => Vec3::new(u, -1.0, -v),
+        4 => Vec3::new(u, -v, 1.0),
+        _ => Vec3::new(-u, -v, -1.0),
+    }
+}
+
+/// Inversa de `face_direction`: la cara es la del eje de mayor magnitud
+fn direction_to_face(direction: &Vec3) -> (usize, f32, f32) {
+    let (x, y, z) = (direction.x, direction.y, direction.z);
+    let (ax, ay, az) = (x.abs(), y.abs(), z.abs());
+    if ax >= ay && ax >= az {
+        if x > 0.0 {
+            (0, -z / ax, -y / ax)
+        } else {
+            (1, z / ax, -y / ax)
+        }
+    } else if ay >= az {
+        if y > 0.0 {
+            (2, x / ay, z / ay)
+        } else {
+            (3, x / ay, -z / ay)
+        }
+    } else if z > 0.0 {
+        (4, x / az, -y / az)
+    } else {
+        (5, -x / az, -y / az)
+    }
+}
+
+/// Cielo de fondo horneado en un cubemap al arrancar: cada cuadro solo cuesta una
+/// búsqueda por píxel y, como depende solo de la rotación de la cámara, queda en el infinito
+pub struct Skybox {
+    cubemap: Cubemap,
+}
+
+impl Skybox {
+    pub fn bake(noise: &FastNoiseLite, material: &NebulaMaterial, resolution: usize) -> Self {
+        Skybox {
+            cubemap: Cubemap::bake(resolution, |direction| material.sample(noise, direction)),
+        }
+    }
+
+    /// Pinta el fondo de todo el framebuffer. Debe llamarse justo después de limpiarlo,
+    /// antes de dibujar nada más: no escribe profundidad.
+    pub fn render(&self, framebuffer: &mut Framebuffer, view_matrix: &Mat4, projection_matrix: &Mat4, viewport_matrix: &Mat4) {
+        // Sin la traslación de la vista la cámara está en el origen y el cielo no se mueve con ella
+        let mut rotation = *view_matrix;
+        rotation[(0, 3)] = 0.0;
+        rotation[(1, 3)] = 0.0;
+        rotation[(2, 3)] = 0.0;
+        let Some(inverse) = (viewport_matrix * projection_matrix * rotation).try_inverse() else {
+            return;
+        };
+
+        // Los puntos del plano lejano son afines en las coordenadas de pantalla: basta
+        // proyectar tres esquinas e interpolar
+        let far_point = |x: f32, y: f32| {
+            let far_z = (viewport_matrix * Vec4::new(0.0, 0.0, 1.0, 1.0)).z;
+            let point = inverse * Vec4::new(x, y, far_z, 1.0);
+            point.xyz() / point.w
+        };
+        let origin = far_point(0.5, 0.5);
+        let step_x = far_point(1.5, 0.5) - origin;
+        let step_y = far_point(0.5, 1.5) - origin;
+
+        for y in 0..framebuffer.height {
+            let row = origin + step_y * y as f32;
+            for x in 0..framebuffer.width {
+                let direction = row + step_x * x as f32;
+                framebuffer.buffer[y * framebuffer.width + x] = self.cubemap.sample(&direction).to_hex();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn axis_directions_hit_face_centers() {
+        let axes = [
+            Vec3::new(1.0, 0.0, 0.0),
+            Vec3::new(-1.0, 0.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0),
+            Vec3::new(0.0, -1.0, 0.0),
+            Vec3::new(0.0, 0.0, 1.0),
+            Vec3::new(0.0, 0.0, -1.0),
+        ];
+        for (face, axis) in axes.iter().enumerate() {
+            assert_eq!(direction_to_face(axis), (face, 0.0, 0.0));
+            assert_eq!(direction_to_face(&(axis * 7.0)), (face, 0.0, 0.0));
+        }
+    }
+
+    #[test]
+    fn direction_to_face_inverts_face_direction() {
+        let steps = [-0.95, -0.5, -0.1, 0.0, 0.3, 0.75, 0.95];
+        for face in 0..6 {
+            for &u in &steps {
+                for &v in &steps {
+                    let (hit, hit_u, hit_v) = direction_to_face(&face_direction(face, u, v));
+                    assert_eq!(hit, face, "face for ({}, {}) on face {}", u, v, face);
+                    assert!((hit_u - u).abs() < 1e-5 && (hit_v - v).abs() < 1e-5, "uv ({}, {}) on face {}", u, v, face);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn sample_reads_the_face_of_the_direction() {
+        // Cada texel guarda la cara que le tocó al hornear
+        let cubemap = Cubemap::bake(4, |direction| {
+            let (face, _, _) = direction_to_face(direction);
+            Color::new(face as u8 * 40, 0, 0)
+        });
+        assert_eq!(cubemap.sample(&Vec3::new(0.0, 0.0, -3.0)).r, 200);
+        assert_eq!(cubemap.sample(&Vec3::new(0.2, -1.0, 0.1)).r, 120);
+        assert_eq!(cubemap.sample(&Vec3::new(-2.0, 0.5, 0.5)).r, 40);
+    }
+}
@@ -0,0 +1,223 @@
+// src/solar.rs
+
+use nalgebra_glm::Vec3;
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+use crate::color::Color;
+use crate::fragment::BlendMode;
+use crate::lighting::smoothstep;
+use crate::particles::{Curve, Emitter, Force, Gradient, perpendicular_basis};
+
+/// Material de una estrella: fotosfera con granulación, manchas y oscurecimiento
+/// del limbo, con el color dado por la temperatura. Los ángulos están en radianes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SolarMaterial {
+    pub temperature: f32,            // Temperatura efectiva de la fotosfera (K)
+    pub brightness: f32,             // Escala del brillo emitido en el centro del disco
+    pub granulation_scale: f32,      // Escala del ruido celular (más alta = gránulos más pequeños)
+    pub granulation_contrast: f32,   // Diferencia de temperatura entre gránulos y surcos (K)
+    pub granulation_lifetime: f32,   // Segundos que tarda en renovarse el patrón de gránulos
+    pub limb_linear: f32,            // Coeficientes de la ley cuadrática de oscurecimiento del limbo
+    pub limb_quadratic: f32,
+    pub differential_rotation: f32,  // Cuánto más lento giran los polos que el ecuador (rad/s)
+    pub spots: Vec<SunspotGroup>,
+    pub activity: SolarActivity,
+}
+
+/// Grupo bipolar de manchas: una mancha líder y una seguidora más pequeña detrás de ella
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SunspotGroup {
+    pub latitude: f32,
+    pub longitude: f32,    // Longitud inicial de la mancha líder
+    pub radius: f32,       // Radio angular de la penumbra de la líder
+    pub separation: f32,   // Distancia en longitud hasta la seguidora
+    pub drift_speed: f32,  // Deriva propia además de la rotación diferencial
+}
+
+/// Protuberancias continuas y fulguraciones ocasionales. Solo nacen junto al limbo visto
+/// desde la cámara: delante del disco serían motas sueltas sobre la fotosfera.
+/// Las partículas se suman al buffer de emisión.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SolarActivity {
+    pub prominence_color: Color,
+    pub prominence_rate: f32,  // Partículas por segundo
+    pub prominence_speed: f32, // Rapidez de salida; la gravedad las hace caer en arco
+    pub gravity: f32,          // Atracción de la estrella a distancia 1
+    pub flare_color: Color,
+    pub flare_interval: f32,   // Segundos entre fulguraciones
+    pub flare_duration: f32,   // Segundos que dura cada una
+    pub flare_rate: f32,
+    pub flare_speed: f32,
+    pub limb_width: f32,       // Semiancho (radianes) de la franja junto al limbo donde nacen
+    pub seed: u64,             // Semilla de la posición de cada fulguración
+}
+
+/// Temperatura relativa de la umbra y la penumbra respecto a la fotosfera
+const UMBRA_TEMPERATURE: f32 = 0.68;
+const PENUMBRA_TEMPERATURE: f32 = 0.88;
+
+impl SolarMaterial {
+    /// Estrella de tipo G como el sol, con dos grupos de manchas en latitudes medias
+    pub fn sun_like() -> Self {
+        SolarMaterial {
+            temperature: 5778.0,
+            brightness: 0.45,
+            granulation_scale: 20.0,
+            granulation_contrast: 250.0,
+            granulation_lifetime: 8.0,
+            limb_linear: 0.47,
+            limb_quadratic: 0.23,
+            differential_rotation: 0.06,
+            spots: vec![
+                SunspotGroup {
+                    latitude: 0.3,
+                    longitude: 0.4,
+                    radius: 0.16,
+                    separation: 0.3,
+                    drift_speed: 0.01,
+                },
+                SunspotGroup {
+                    latitude: -0.22,
+                    longitude: 2.2,
+                    radius: 0.12,
+                    separation: 0.25,
+                    drift_speed: -0.015,
+                },
+            ],
+            activity: SolarActivity {
+                prominence_color: Color::new(255, 90, 40),
+                prominence_rate: 40.0,
+                prominence_speed: 0.6,
+                gravity: 1.2,
+                flare_color: Color::new(255, 220, 160),
+                flare_interval: 6.0,
+                flare_duration: 1.5,
+                flare_rate: 120.0,
+                flare_speed: 1.2,
+                limb_width: 0.3,
+                seed: 99,
+            },
+        }
+    }
+
+    /// Ley cuadrática de oscurecimiento del limbo: intensidad relativa según el coseno
+    /// entre la normal y la dirección hacia el observador
+    pub fn limb_darkening(&self, mu: f32) -> f32 {
+        let edge = 1.0 - mu.clamp(0.0, 1.0);
+        (1.0 - self.limb_linear * edge - self.limb_quadratic * edge * edge).max(0.0)
+    }
+
+    /// Factor de temperatura por las manchas (1 = fotosfera limpia) en una dirección del
+    /// espacio del modelo, con la rotación diferencial según la latitud
+    pub fn spot_temperature(&self, direction: &Vec3, time: f32) -> f32 {
+        let latitude = direction.y.clamp(-1.0, 1.0).asin();
+        let mut factor: f32 = 1.0;
+
+        for group in &self.spots {
+            // El ecuador adelanta a las latitudes altas
+            let drift = (group.drift_speed - self.differential_rotation * group.latitude.sin().powi(2)) * time;
+            let members = [
+                (group.longitude + drift, group.radius),
+                (group.longitude + drift - group.separation, group.radius * 0.7),
+            ];
+            for (longitude, radius) in members {
+                let center = Vec3::new(
+                    group.latitude.cos() * longitude.cos(),
+                    group.latitude.sin(),
+                    group.latitude.cos() * longitude.sin(),
+                );
+                // Solo las manchas de la misma franja de latitud se tocan
+                if (latitude - group.latitude).abs() > radius * 2.0 {
+                    continue;
+                }
+                let distance = direction.dot(&center).clamp(-1.0, 1.0).acos();
+                let penumbra = 1.0 - smoothstep(radius * 0.8, radius, distance);
+                let umbra = 1.0 - smoothstep(radius * 0.35, radius * 0.5, distance);
+                let spot = 1.0 - penumbra * (1.0 - PENUMBRA_TEMPERATURE) - umbra * (PENUMBRA_TEMPERATURE - UMBRA_TEMPERATURE);
+                factor = factor.min(spot);
+            }
+        }
+
+        factor
+    }
+
+    /// Emisor de protuberancias: nace junto al limbo (ver `steer_prominences`) y la gravedad
+    /// lo devuelve a la fotosfera
+    pub fn prominence_emitter(&self, center: Vec3, radius: f32) -> Emitter {
+        let activity = &self.activity;
+        Emitter::new(
+            Vec3::new(0.0, 1.0, 0.0),
+            Curve::new(activity.prominence_speed * 0.5, activity.prominence_speed),
+            activity.prominence_rate,
+            Curve::new(1.5, 3.0),
+            Curve::new(radius * 0.04, radius * 0.08),
+            Gradient::new(activity.prominence_color, Color::new(160, 30, 20)),
+            Curve::new(0.8, 0.0),
+        )
+        .with_band(activity.limb_width)
+        .with_shell(radius, 0.6)
+        .with_force(Force::Radial { center, strength: -activity.gravity })
+        .with_blend_mode(BlendMode::Emissive)
+    }
+
+    /// Emisor de fulguraciones: apagado salvo durante cada evento (ver `steer_flare`)
+    pub fn flare_emitter(&self, center: Vec3, radius: f32) -> Emitter {
+        let activity = &self.activity;
+        Emitter::new(
+            Vec3::new(0.0, 1.0, 0.0),
+            Curve::new(activity.flare_speed * 0.6, activity.flare_speed),
+            0.0,
+            Curve::new(0.6, 1.2),
+            Curve::new(radius * 0.06, radius * 0.15),
+            Gradient::new(activity.flare_color, activity.prominence_color),
+            Curve::new(1.0, 0.0),
+        )
+        .with_spread(0.08)
+        .with_shell(radius, 0.4)
+        .with_force(Force::Radial { center, strength: -activity.gravity })
+        .with_blend_mode(BlendMode::Emissive)
+    }
+
+    /// Sigue a la estrella y enciende el emisor durante cada fulguración, que aparece en una
+    /// dirección sorteada por evento (siempre la misma para el mismo tiempo) llevada al limbo
+    /// visto desde `eye`
+    pub fn steer_flare(&self, flare: &mut Emitter, center: Vec3, eye: Vec3, time: f32) {
+        let activity = &self.activity;
+        let phase = time / activity.flare_interval;
+        let event = phase.floor();
+        let mut rng = StdRng::seed_from_u64(activity.seed.wrapping_add(event as i64 as u64));
+        let direction = Vec3::new(rng.gen_range(-1.0..1.0), rng.gen_range(-0.6..0.6), rng.gen_range(-1.0..1.0));
+        let view = view_axis(center, eye);
+        let on_limb = direction - view * direction.dot(&view);
+
+        flare.position = center;
+        flare.direction = if on_limb.magnitude_squared() > 1e-6 {
+            on_limb
+        } else {
+            perpendicular_basis(&view).0
+        };
+        flare.rate = if phase.fract() * activity.flare_interval < activity.flare_duration {
+            activity.flare_rate
+        } else {
+            0.0
+        };
+        flare.forces = vec![Force::Radial { center, strength: -activity.gravity }];
+    }
+
+    /// Sigue a la estrella con el emisor de protuberancias y orienta su franja hacia `eye`
+    pub fn steer_prominences(&self, prominences: &mut Emitter, center: Vec3, eye: Vec3) {
+        prominences.position = center;
+        prominences.direction = view_axis(center, eye);
+        prominences.forces = vec![Force::Radial { center, strength: -self.activity.gravity }];
+    }
+}
+
+/// Dirección de la estrella hacia la cámara; el limbo es el círculo máximo perpendicular a ella
+fn view_axis(center: Vec3, eye: Vec3) -> Vec3 {
+    let offset = eye - center;
+    if offset.magnitude_squared() > 0.0 {
+        offset.normalize()
+    } else {
+        Vec3::new(0.0, 0.0, 1.0)
+    }
+}
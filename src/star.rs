@@ -1,19 +1,19 @@
 // src/star.rs
 
-use nalgebra_glm::{Vec3, Mat4};
+use nalgebra_glm::Vec3;
 use crate::color::Color;
 
 pub struct Star {
-    pub position: Vec3,     // Posición en el espacio 3D
-    pub brightness: f32,    // Brillo de la estrella (0.0 a 1.0)
+    pub direction: Vec3,    // Dirección unitaria: las estrellas están en el infinito
+    pub magnitude: f32,     // Magnitud visual aparente: menor es más brillante
     pub color: Color,       // Color de la estrella
 }
 
 impl Star {
-    pub fn new(position: Vec3, brightness: f32, color: Color) -> Self {
+    pub fn new(direction: Vec3, magnitude: f32, color: Color) -> Self {
         Star {
-            position,
-            brightness: brightness.clamp(0.0, 1.0),
+            direction: direction.normalize(),
+            magnitude,
             color,
         }
     }
@@ -1,93 +1,164 @@
 // src/stars.rs
 
-use nalgebra::{Vector3, Point3, Matrix4, Vector4};
+use std::path::Path;
+use nalgebra_glm::{Vec3, Vec4, Mat4};
 use crate::framebuffer::Framebuffer;
-use crate::camera::Camera;
 use crate::color::Color;
 use crate::star::Star;
-use rand::Rng;
+use crate::catalog::load_catalog;
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
 
+/// Magnitudes del campo aleatorio: de la más brillante del cielo al límite a simple vista
+const BRIGHTEST_MAGNITUDE: f32 = -1.5;
+const FAINTEST_MAGNITUDE: f32 = 6.5;
+/// Magnitud cuyo flujo (ya con la respuesta del ojo) vale 1
+const REFERENCE_MAGNITUDE: f32 = 1.0;
+/// Escala del flujo que se reparte en la huella de cada estrella
+const EXPOSURE: f32 = 4.0;
+/// Desviación en píxeles de la huella gaussiana de una estrella débil
+const BASE_SIGMA: f32 = 0.45;
+/// Las estrellas más brillantes que esta magnitud se ensanchan `SIGMA_PER_MAGNITUDE` por magnitud
+const SPREAD_MAGNITUDE: f32 = 2.0;
+const SIGMA_PER_MAGNITUDE: f32 = 0.25;
+/// Variación relativa del brillo por el centelleo
+const TWINKLE: f32 = 0.25;
+
+/// Clase espectral de Harvard: rango de temperaturas efectivas (K) y fracción de las estrellas
+/// visibles a simple vista que pertenecen a ella. Las gigantes calientes son raras pero muy
+/// luminosas, así que están sobrerrepresentadas respecto a su abundancia real.
+struct SpectralClass {
+    min_temperature: f32,
+    max_temperature: f32,
+    fraction: f32,
+}
+
+const SPECTRAL_CLASSES: [SpectralClass; 7] = [
+    SpectralClass { min_temperature: 30000.0, max_temperature: 40000.0, fraction: 0.01 }, // O
+    SpectralClass { min_temperature: 10000.0, max_temperature: 30000.0, fraction: 0.10 }, // B
+    SpectralClass { min_temperature: 7500.0, max_temperature: 10000.0, fraction: 0.22 },  // A
+    SpectralClass { min_temperature: 6000.0, max_temperature: 7500.0, fraction: 0.19 },   // F
+    SpectralClass { min_temperature: 5200.0, max_temperature: 6000.0, fraction: 0.14 },   // G
+    SpectralClass { min_temperature: 3700.0, max_temperature: 5200.0, fraction: 0.30 },   // K
+    SpectralClass { min_temperature: 2400.0, max_temperature: 3700.0, fraction: 0.04 },   // M
+];
+
+/// Temperatura de una estrella al azar según la distribución de `SPECTRAL_CLASSES`
+fn sample_temperature<R: Rng>(rng: &mut R) -> f32 {
+    let total: f32 = SPECTRAL_CLASSES.iter().map(|class| class.fraction).sum();
+    let mut choice = rng.gen_range(0.0..total);
+    for class in &SPECTRAL_CLASSES {
+        if choice < class.fraction {
+            return rng.gen_range(class.min_temperature..class.max_temperature);
+        }
+        choice -= class.fraction;
+    }
+    SPECTRAL_CLASSES[SPECTRAL_CLASSES.len() - 1].min_temperature
+}
+
+/// Magnitud al azar siguiendo el conteo de estrellas brillantes, que crece como 10^(0.5 m):
+/// por cada estrella de una magnitud hay unas tres de la siguiente
+fn sample_magnitude<R: Rng>(rng: &mut R) -> f32 {
+    let u: f32 = rng.gen_range(f32::EPSILON..1.0);
+    (FAINTEST_MAGNITUDE + 2.0 * u.log10()).max(BRIGHTEST_MAGNITUDE)
+}
+
+/// Estrellas del fondo en el infinito: al azar o leídas de un catálogo real
 pub struct StarField {
     pub stars: Vec<Star>,
 }
 
 impl StarField {
     /// Genera un campo de estrellas aleatorio
-    pub fn new(num_stars: usize, radius: f32) -> Self {
+    pub fn new(num_stars: usize) -> Self {
+        Self::from_rng(num_stars, &mut rand::thread_rng())
+    }
+
+    /// Genera un campo de estrellas reproducible a partir de una semilla
+    pub fn with_seed(num_stars: usize, seed: u64) -> Self {
+        Self::from_rng(num_stars, &mut StdRng::seed_from_u64(seed))
+    }
+
+    /// Estrellas de un catálogo (ver `catalog::load_catalog`), con las constelaciones reales
+    pub fn from_catalog(path: &Path) -> Result<Self, String> {
+        Ok(StarField { stars: load_catalog(path)? })
+    }
+
+    fn from_rng<R: Rng>(num_stars: usize, rng: &mut R) -> Self {
         let mut stars = Vec::with_capacity(num_stars);
-        let mut rng = rand::thread_rng();
 
         for _ in 0..num_stars {
-            // Generar una posición aleatoria dentro de una esfera
+            // Dirección uniforme sobre la esfera
+            let z: f32 = rng.gen_range(-1.0..1.0);
             let theta: f32 = rng.gen_range(0.0..2.0 * std::f32::consts::PI);
-            let phi: f32 = rng.gen_range(0.0..std::f32::consts::PI);
-            let r: f32 = rng.gen_range(0.0..radius);
-
-            let x = r * theta.cos() * phi.sin();
-            let y = r * theta.sin() * phi.sin();
-            let z = r * phi.cos();
-
-            // Generar brillo y color aleatorio
-            let brightness: f32 = rng.gen_range(0.5..1.0); // Evitar estrellas muy débiles
-            let color = Color::new(
-                rng.gen_range(200..256) as u8,
-                rng.gen_range(200..256) as u8,
-                rng.gen_range(200..256) as u8,
-            );
-
-            stars.push(Star::new(Vector3::new(x, y, z), brightness, color));
+            let ring = (1.0 - z * z).sqrt();
+            let direction = Vec3::new(ring * theta.cos(), ring * theta.sin(), z);
+
+            // Magnitud aleatoria y color según la temperatura de su clase espectral
+            let magnitude = sample_magnitude(rng);
+            let color = Color::from_temperature(sample_temperature(rng));
+
+            stars.push(Star::new(direction, magnitude, color));
         }
 
         StarField { stars }
     }
 
-    /// Renderiza las estrellas en el framebuffer
-    pub fn render(&self, framebuffer: &mut Framebuffer, camera: &Camera, projection_matrix: &Matrix4<f32>, viewport_matrix: &Matrix4<f32>) {
-        for star in &self.stars {
-            // Transformar la posición de la estrella al espacio de cámara
-            let model_matrix: Matrix4<f32> = Matrix4::identity(); // Sin transformación adicional
-    
-            // Crear matriz de vista-proyección usando look_at_rh
-            let view_matrix = Matrix4::look_at_rh(
-                &Point3::new(camera.eye.x, camera.eye.y, camera.eye.z),
-                &Point3::new(camera.center.x, camera.center.y, camera.center.z),
-                &Vector3::from(camera.up),
-            );
-    
-            let view_projection = projection_matrix * view_matrix;
-    
-            // Convertir Vec3 a Point3
-            let position_point = Point3::new(star.position.x, star.position.y, star.position.z);
-    
-            // Convertir Point3 a Vec4
-            let position_vec4 = position_point.to_homogeneous(); // Añade el componente w=1.0
-    
-            let clip_space = view_projection * position_vec4;
-    
-            // Ignorar estrellas detrás del plano cercano o lejano
-            if clip_space.w <= 0.0 {
+    /// Dibuja las estrellas en el infinito como huellas gaussianas menores que un píxel que
+    /// conservan su flujo, más anchas para las más brillantes y con centelleo. Va justo después
+    /// del cielo y antes de los cuerpos: no escribe profundidad, así que los cuerpos las tapan.
+    pub fn render(&self, framebuffer: &mut Framebuffer, view_matrix: &Mat4, projection_matrix: &Mat4, viewport_matrix: &Mat4, time: f32) {
+        // Una sola matriz por cuadro; con w = 0 la traslación de la vista no influye y solo
+        // cuenta la rotación de la cámara
+        let view_projection = projection_matrix * view_matrix;
+
+        for (index, star) in self.stars.iter().enumerate() {
+            let direction = star.direction;
+            let clip = view_projection * Vec4::new(direction.x, direction.y, direction.z, 0.0);
+            if clip.w <= 0.0 {
+                continue;
+            }
+            let ndc = clip.xyz() / clip.w;
+            let screen = viewport_matrix * Vec4::new(ndc.x, ndc.y, 0.0, 1.0);
+
+            // La respuesta del ojo es más suave que el flujo: se usa su raíz cuadrada
+            let flux = 10f32.powf(-0.2 * (star.magnitude - REFERENCE_MAGNITUDE)) * EXPOSURE * twinkle(index, time);
+            let sigma = BASE_SIGMA + (SPREAD_MAGNITUDE - star.magnitude).max(0.0) * SIGMA_PER_MAGNITUDE;
+            let peak = flux / (2.0 * std::f32::consts::PI * sigma * sigma);
+            if peak < 0.5 / 255.0 {
                 continue;
             }
-    
-            // Convertir a NDC
-            let ndc = Vector3::new(
-                clip_space.x / clip_space.w,
-                clip_space.y / clip_space.w,
-                clip_space.z / clip_space.w,
-            );
-    
-            // Convertir a coordenadas de pantalla
-            let screen_space = viewport_matrix * Vector4::new(ndc.x, ndc.y, ndc.z, 1.0);
-            let x = screen_space.x.round() as i32;
-            let y = screen_space.y.round() as i32;
-    
-            // Establecer el pixel si está dentro de la pantalla
-            if x >= 0 && x < framebuffer.width as i32 && y >= 0 && y < framebuffer.height as i32 {
-                // Aplicar el brillo al color
-                let color = star.color * star.brightness;
-                framebuffer.set_pixel(x, y, color, ndc.z);
+
+            let radius = (sigma * 3.0).ceil();
+            let min_x = (screen.x - radius).floor().max(0.0) as usize;
+            let min_y = (screen.y - radius).floor().max(0.0) as usize;
+            let max_x = (screen.x + radius).ceil().min(framebuffer.width as f32 - 1.0);
+            let max_y = (screen.y + radius).ceil().min(framebuffer.height as f32 - 1.0);
+            if max_x < 0.0 || max_y < 0.0 {
+                continue;
+            }
+
+            for y in min_y..=max_y as usize {
+                for x in min_x..=max_x as usize {
+                    let dx = x as f32 + 0.5 - screen.x;
+                    let dy = y as f32 + 0.5 - screen.y;
+                    let weight = peak * (-(dx * dx + dy * dy) / (2.0 * sigma * sigma)).exp();
+                    let color = star.color * weight;
+                    if color.is_black() {
+                        continue;
+                    }
+                    framebuffer.set_current_color(color.to_hex());
+                    framebuffer.add_point(x, y, 1.0);
+                }
             }
         }
     }
-    
+}
+
+/// Factor de centelleo de una estrella: oscila con fase y frecuencia propias de su índice
+fn twinkle(index: usize, time: f32) -> f32 {
+    let hash = |seed: f32| ((index as f32 * 12.9898 + seed).sin() * 43758.547).fract().abs();
+    let phase = hash(0.0) * 2.0 * std::f32::consts::PI;
+    let frequency = 1.5 + hash(78.233) * 3.0;
+    1.0 + TWINKLE * (time * frequency + phase).sin()
 }
@@ -0,0 +1,341 @@
+// src/texture.rs
+
+use std::path::Path;
+use std::sync::Arc;
+use image::RgbaImage;
+use nalgebra_glm::{Vec2, Vec4};
+use crate::material::TerrainMaterial;
+
+/// Un nivel de la cadena de mipmaps. Los texels se guardan en RGBA de 8 bits: cuatro veces
+/// menos memoria que en coma flotante, lo que importa porque cada fragmento lee varios mapas
+struct MipLevel {
+    width: usize,
+    height: usize,
+    texels: Vec<[u8; 4]>,
+}
+
+impl MipLevel {
+    /// Índice del texel: en horizontal se repite (la longitud da la vuelta) y en vertical se
+    /// recorta en los polos. Casi siempre `x` ya está dentro y se evita la división de `rem_euclid`
+    fn index(&self, x: isize, y: isize) -> usize {
+        let width = self.width as isize;
+        let x = if (0..width).contains(&x) { x } else { x.rem_euclid(width) };
+        let y = y.clamp(0, self.height as isize - 1);
+        (y * width + x) as usize
+    }
+
+    fn texel(&self, x: isize, y: isize) -> Vec4 {
+        let [r, g, b, a] = self.texels[self.index(x, y)];
+        Vec4::new(r as f32, g as f32, b as f32, a as f32) / 255.0
+    }
+
+    /// Los cuatro vecinos del muestreo bilineal en `uv`, con (0, 0) en la esquina superior
+    /// izquierda, y sus pesos multiplicados por `weight`
+    fn taps(&self, uv: &Vec2, weight: f32) -> [(usize, f32); 4] {
+        let x = uv.x * self.width as f32 - 0.5;
+        let y = uv.y * self.height as f32 - 0.5;
+        let (x0, y0) = (x.floor(), y.floor());
+        let (tx, ty) = (x - x0, y - y0);
+        let (x0, y0) = (x0 as isize, y0 as isize);
+
+        [
+            (self.index(x0, y0), (1.0 - tx) * (1.0 - ty) * weight),
+            (self.index(x0 + 1, y0), tx * (1.0 - ty) * weight),
+            (self.index(x0, y0 + 1), (1.0 - tx) * ty * weight),
+            (self.index(x0 + 1, y0 + 1), tx * ty * weight),
+        ]
+    }
+
+    /// Suma ponderada de los vecinos canal a canal, en [0, 255]
+    fn gather(&self, taps: &[(usize, f32); 4], sum: &mut [f32; 4]) {
+        for &(index, weight) in taps {
+            let texel = self.texels[index];
+            for channel in 0..4 {
+                sum[channel] += texel[channel] as f32 * weight;
+            }
+        }
+    }
+
+    /// Nivel siguiente: cada texel promedia el bloque de 2x2 que cubre. Con un tamaño impar
+    /// el último bloque de la fila o la columna incluye también el texel sobrante.
+    fn downsample(&self) -> MipLevel {
+        let width = (self.width / 2).max(1);
+        let height = (self.height / 2).max(1);
+        // Texels de origen [inicio, fin) del bloque `i` de `count` en un eje de `size`
+        let block = |i: usize, count: usize, size: usize| (i * 2, if i + 1 == count { size } else { i * 2 + 2 });
+        let mut texels = Vec::with_capacity(width * height);
+        for y in 0..height {
+            let (y0, y1) = block(y, height, self.height);
+            for x in 0..width {
+                let (x0, x1) = block(x, width, self.width);
+                let mut sum = Vec4::zeros();
+                for sy in y0..y1 {
+                    for sx in x0..x1 {
+                        sum += self.texel(sx as isize, sy as isize);
+                    }
+                }
+                let count = ((x1 - x0) * (y1 - y0)) as f32;
+                texels.push(sum.map(|channel| (channel / count * 255.0).round() as u8).into());
+            }
+        }
+        MipLevel { width, height, texels }
+    }
+}
+
+/// Imagen con mipmaps para mapas equirectangulares: `u` es la longitud y `v` la latitud,
+/// de norte (0) a sur (1)
+pub struct Texture {
+    levels: Vec<MipLevel>,
+}
+
+impl Texture {
+    /// Carga un PNG o JPG con el crate `image`
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let image = image::open(path).map_err(|e| format!("Failed to load texture {}: {}", path.display(), e))?;
+        Ok(Texture::from_image(&image.to_rgba8()))
+    }
+
+    /// Construye la cadena de mipmaps completa, hasta un texel
+    pub fn from_image(image: &RgbaImage) -> Self {
+        let base = MipLevel {
+            width: image.width().max(1) as usize,
+            height: image.height().max(1) as usize,
+            texels: image.pixels().map(|pixel| pixel.0).collect(),
+        };
+
+        let mut levels = vec![base];
+        while levels.last().is_some_and(|level| level.width > 1 || level.height > 1) {
+            let next = levels[levels.len() - 1].downsample();
+            levels.push(next);
+        }
+        Texture { levels }
+    }
+
+    pub fn width(&self) -> usize {
+        self.levels[0].width
+    }
+
+    pub fn height(&self) -> usize {
+        self.levels[0].height
+    }
+
+    /// Color medio de la imagen: el único texel del último nivel de mipmap
+    pub fn mean(&self) -> Vec4 {
+        self.levels[self.levels.len() - 1].texel(0, 0)
+    }
+
+    /// Nivel de mipmap para un fragmento que cubre `uv_footprint` de área en UV: el que
+    /// deja aproximadamente un texel por píxel
+    pub fn level_of_detail(&self, uv_footprint: f32) -> f32 {
+        let texels_per_pixel = uv_footprint * (self.width() * self.height()) as f32;
+        (0.5 * fast_log2(texels_per_pixel.max(1e-12))).clamp(0.0, (self.levels.len() - 1) as f32)
+    }
+
+    /// Resuelve el muestreo trilineal en `uv`: bilineal en los dos niveles más cercanos al
+    /// de `uv_footprint` y mezcla entre ellos
+    pub fn locate(&self, uv: &Vec2, uv_footprint: f32) -> SamplePoint {
+        let lod = self.level_of_detail(uv_footprint);
+        let fine = lod.floor() as usize;
+        let coarse = (fine + 1).min(self.levels.len() - 1);
+        let fraction = lod - fine as f32;
+
+        SamplePoint {
+            uv: *uv,
+            uv_footprint,
+            width: self.width(),
+            height: self.height(),
+            levels: [fine, coarse],
+            taps: [self.levels[fine].taps(uv, 1.0 - fraction), self.levels[coarse].taps(uv, fraction)],
+        }
+    }
+
+    /// Valor en [0, 1] de un muestreo ya resuelto; si se resolvió para otro tamaño de textura,
+    /// se vuelve a resolver para esta
+    pub fn sample_at(&self, point: &SamplePoint) -> Vec4 {
+        if point.width != self.width() || point.height != self.height() {
+            return self.sample(&point.uv, point.uv_footprint);
+        }
+
+        let mut sum = [0.0f32; 4];
+        for (level, taps) in point.levels.iter().zip(&point.taps) {
+            self.levels[*level].gather(taps, &mut sum);
+        }
+        Vec4::new(sum[0], sum[1], sum[2], sum[3]) / 255.0
+    }
+
+    /// Muestreo trilineal en `uv` eligiendo el nivel según la huella del fragmento
+    pub fn sample(&self, uv: &Vec2, uv_footprint: f32) -> Vec4 {
+        self.sample_at(&self.locate(uv, uv_footprint))
+    }
+}
+
+/// Muestreo trilineal ya resuelto para un tamaño de textura: los dos niveles de mipmap con
+/// sus vecinos y pesos. Los mapas de un cuerpo suelen compartir tamaño (los horneados
+/// siempre), así que cada fragmento lo resuelve una vez y lo reutiliza en todos ellos.
+pub struct SamplePoint {
+    uv: Vec2,
+    uv_footprint: f32,
+    width: usize,
+    height: usize,
+    levels: [usize; 2],
+    taps: [[(usize, f32); 4]; 2],
+}
+
+/// Mapas de superficie de un cuerpo. Cada uno es opcional y se mezcla con el material
+/// procedural según `blend`: 0 deja solo el ruido y 1 solo las texturas.
+#[derive(Clone)]
+pub struct SurfaceTextures {
+    pub albedo: Option<Arc<Texture>>,
+    pub specular: Option<Arc<Texture>>, // Brillo especular en el canal rojo (blanco = máximo)
+    pub normal: Option<Arc<Texture>>,   // Normales en espacio tangente: x al este, y al norte
+    pub night: Option<Arc<Texture>>,    // Luces nocturnas que se emiten en el lado de noche
+    pub blend: f32,
+    pub normal_strength: f32,           // 0 = normal de la esfera, 1 = normal del mapa
+}
+
+impl SurfaceTextures {
+    pub fn new(blend: f32) -> Self {
+        SurfaceTextures {
+            albedo: None,
+            specular: None,
+            normal: None,
+            night: None,
+            blend: blend.clamp(0.0, 1.0),
+            normal_strength: 1.0,
+        }
+    }
+
+    pub fn with_albedo(mut self, texture: Arc<Texture>) -> Self {
+        self.albedo = Some(texture);
+        self
+    }
+
+    pub fn with_specular(mut self, texture: Arc<Texture>) -> Self {
+        self.specular = Some(texture);
+        self
+    }
+
+    pub fn with_normal(mut self, texture: Arc<Texture>, strength: f32) -> Self {
+        self.normal = Some(texture);
+        self.normal_strength = strength;
+        self
+    }
+
+    pub fn with_night(mut self, texture: Arc<Texture>) -> Self {
+        self.night = Some(texture);
+        self
+    }
+
+    /// Muestreo resuelto sobre el primer mapa presente, para reutilizarlo en los demás
+    pub fn locate(&self, uv: &Vec2, uv_footprint: f32) -> Option<SamplePoint> {
+        [&self.albedo, &self.specular, &self.normal, &self.night]
+            .into_iter()
+            .find_map(|map| map.as_ref())
+            .map(|map| map.locate(uv, uv_footprint))
+    }
+
+    /// Si los mapas sustituyen por completo al material procedural de `material`, de modo que
+    /// el shader puede saltarse el ruido
+    pub fn replaces(&self, material: &TerrainMaterial) -> bool {
+        self.blend >= 1.0
+            && self.albedo.is_some()
+            && self.specular.is_some()
+            && (material.bump_strength <= 0.0 || self.normal.is_some())
+            && (material.city_lights.is_none() || self.night.is_some())
+    }
+
+    /// Carga los mapas `albedo`, `specular`, `normal` y `night` (PNG o JPG) que haya en
+    /// `directory`. Devuelve `None` si no hay ninguno.
+    pub fn load_dir(directory: &Path, blend: f32) -> Result<Option<Self>, String> {
+        let find = |name: &str| {
+            ["png", "jpg", "jpeg"]
+                .iter()
+                .map(|extension| directory.join(format!("{}.{}", name, extension)))
+                .find(|path| path.is_file())
+                .map(|path| Texture::load(&path).map(Arc::new))
+                .transpose()
+        };
+
+        let mut textures = SurfaceTextures::new(blend);
+        let mut found = false;
+        if let Some(albedo) = find("albedo")? {
+            textures = textures.with_albedo(albedo);
+            found = true;
+        }
+        if let Some(specular) = find("specular")? {
+            textures = textures.with_specular(specular);
+            found = true;
+        }
+        if let Some(normal) = find("normal")? {
+            textures = textures.with_normal(normal, 1.0);
+            found = true;
+        }
+        if let Some(night) = find("night")? {
+            textures = textures.with_night(night);
+            found = true;
+        }
+        Ok(found.then_some(textures))
+    }
+}
+
+/// Logaritmo en base 2 aproximado (error menor que 0.01) a partir de los bits del número:
+/// basta para elegir el nivel de mipmap y es mucho más barato que `f32::log2`
+fn fast_log2(value: f32) -> f32 {
+    let bits = value.to_bits();
+    let exponent = ((bits >> 23) & 0xff) as f32 - 128.0;
+    let mantissa = f32::from_bits((bits & 0x007f_ffff) | 0x3f80_0000); // En [1, 2)
+    exponent + (-0.344_848_43 * mantissa + 2.024_665_8) * mantissa - 0.674_877_6
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fast_log2_is_close_to_log2() {
+        let mut value = 1e-6f32;
+        while value < 1e6 {
+            assert!((fast_log2(value) - value.log2()).abs() < 0.01, "log2({})", value);
+            value *= 1.37;
+        }
+        for power in -10..10 {
+            assert!((fast_log2(2.0f32.powi(power)) - power as f32).abs() < 0.01);
+        }
+    }
+
+    #[test]
+    fn level_of_detail_leaves_one_texel_per_pixel() {
+        let texture = Texture::from_image(&RgbaImage::new(256, 128));
+        let texels = (256 * 128) as f32;
+        // Cada nivel reduce el área de un texel a la cuarta parte
+        assert!(texture.level_of_detail(1.0 / texels).abs() < 0.01);
+        assert!((texture.level_of_detail(4.0 / texels) - 1.0).abs() < 0.01);
+        assert!((texture.level_of_detail(64.0 / texels) - 3.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn level_of_detail_stays_inside_the_mip_chain() {
+        let texture = Texture::from_image(&RgbaImage::new(256, 128));
+        // 256x128 baja hasta 1x1 en ocho pasos
+        assert_eq!(texture.level_of_detail(1e-9), 0.0);
+        assert_eq!(texture.level_of_detail(1000.0), 8.0);
+        assert_eq!(texture.level_of_detail(0.0), 0.0);
+    }
+
+    #[test]
+    fn downsample_keeps_the_leftover_texel_of_odd_sizes() {
+        // Columnas 0, 30, 60, 90, 120: el segundo texel promedia las tres últimas
+        let image = RgbaImage::from_fn(5, 1, |x, _| image::Rgba([x as u8 * 30, 0, 0, 255]));
+        let texture = Texture::from_image(&image);
+        let level = &texture.levels[1];
+        assert_eq!((level.width, level.height), (2, 1));
+        assert_eq!(level.texels[0][0], 15);
+        assert_eq!(level.texels[1][0], 90);
+
+        // Un nivel de 3x3 baja a un solo texel con la media de los nueve
+        let image = RgbaImage::from_fn(3, 3, |x, y| image::Rgba([(x + 3 * y) as u8 * 20, 0, 0, 255]));
+        let texture = Texture::from_image(&image);
+        assert_eq!(texture.levels.len(), 2);
+        assert_eq!(texture.levels[1].texels[0][0], 80);
+    }
+}
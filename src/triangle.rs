@@ -1,9 +1,8 @@
 // src/triangle.rs
 
-use nalgebra_glm::{Vec3, dot, Vec4};
+use nalgebra_glm::{Vec3, Vec4};
 use crate::fragment::{Fragment, CelestialType};
 use crate::vertex::Vertex;
-use crate::color::Color;
 
 /// Genera los fragmentos de un triángulo para rasterización
 pub fn triangle(v1: &Vertex, v2: &Vertex, v3: &Vertex, celestial_type: CelestialType) -> Vec<Fragment> {
@@ -12,10 +11,13 @@ pub fn triangle(v1: &Vertex, v2: &Vertex, v3: &Vertex, celestial_type: Celestial
 
     let (min_x, min_y, max_x, max_y) = calculate_bounding_box(&a, &b, &c);
 
-    let light_dir = Vec3::new(0.0, 0.0, 1.0).normalize();
-
     let triangle_area = edge_function(&a, &b, &c);
 
+    // Área en UV por píxel de pantalla: constante en todo el triángulo
+    let (t1, t2, t3) = (v1.tex_coords, v2.tex_coords, v3.tex_coords);
+    let uv_area = ((t2.x - t1.x) * (t3.y - t1.y) - (t3.x - t1.x) * (t2.y - t1.y)).abs();
+    let uv_footprint = uv_area / triangle_area.abs().max(1e-6);
+
     for y in min_y..=max_y {
         for x in min_x..=max_x {
             let point = Vec3::new(x as f32 + 0.5, y as f32 + 0.5, 0.0);
@@ -30,13 +32,6 @@ pub fn triangle(v1: &Vertex, v2: &Vertex, v3: &Vertex, celestial_type: Celestial
                     // Interpolar normales
                     let interpolated_normal = (v1.transformed_normal * w1 + v2.transformed_normal * w2 + v3.transformed_normal * w3).normalize();
 
-                    // Calcular intensidad de luz usando el producto punto
-                    let intensity = dot(&interpolated_normal, &light_dir).max(0.0);
-
-                    // Definir color base y aplicar la intensidad
-                    let base_color = Color::new(100, 100, 100); // Puedes ajustar esto según tus necesidades
-                    let lit_color = base_color * intensity;
-
                     // Interpolar profundidad (z)
                     let depth = a.z * w1 + b.z * w2 + c.z * w3;
 
@@ -46,23 +41,23 @@ pub fn triangle(v1: &Vertex, v2: &Vertex, v3: &Vertex, celestial_type: Celestial
                     // Convert vertex_position to 4D
                     let vertex_position = Vec4::new(vertex_position.x, vertex_position.y, vertex_position.z, 1.0);
 
-                    // Determinar si el fragmento es emisivo basado en el tipo celestial
-                    let emissive = match celestial_type {
-                        CelestialType::Star => true,
-                        _ => false,
-                    };
+                    // Interpolar posición en el espacio del mundo (para la iluminación)
+                    let world_position = v1.world_position * w1 + v2.world_position * w2 + v3.world_position * w3;
+
+                    // Interpolar coordenadas de textura
+                    let tex_coords = t1 * w1 + t2 * w2 + t3 * w3;
 
                     // Crear el fragmento con todos los parámetros requeridos
                     let fragment = Fragment::new(
                         x as f32,
                         y as f32,
-                        lit_color,
                         depth,
                         interpolated_normal,
-                        intensity,
                         vertex_position,
                         celestial_type,
-                    );
+                    )
+                    .with_world_position(world_position)
+                    .with_tex_coords(tex_coords, uv_footprint);
 
                     fragments.push(fragment);
                 }
@@ -84,7 +79,7 @@ fn calculate_bounding_box(v1: &Vec3, v2: &Vec3, v3: &Vec3) -> (i32, i32, i32, i3
 }
 
 /// Calcula las coordenadas barycentric de un punto dentro de un triángulo
-fn barycentric_coordinates(p: &Vec3, a: &Vec3, b: &Vec3, c: &Vec3, area: f32) -> (f32, f32, f32) {
+pub fn barycentric_coordinates(p: &Vec3, a: &Vec3, b: &Vec3, c: &Vec3, area: f32) -> (f32, f32, f32) {
     let w1 = edge_function(&b, &c, p) / area;
     let w2 = edge_function(&c, &a, p) / area;
     let w3 = edge_function(&a, &b, p) / area;
@@ -93,6 +88,6 @@ fn barycentric_coordinates(p: &Vec3, a: &Vec3, b: &Vec3, c: &Vec3, area: f32) ->
 }
 
 /// Calcula la función de borde para determinación de inclusión
-fn edge_function(a: &Vec3, b: &Vec3, c: &Vec3) -> f32 {
+pub fn edge_function(a: &Vec3, b: &Vec3, c: &Vec3) -> f32 {
     (c.x - a.x) * (b.y - a.y) - (c.y - a.y) * (b.x - a.x)
 }
@@ -1,75 +1,96 @@
 // src/uniforms.rs
 
 use nalgebra_glm::Vec3;
-use crate::color::Color;
+use crate::lighting::PointLight;
+use crate::shadows::ShadowCasters;
+use crate::atmosphere::AtmosphereShell;
+use crate::material::{TerrainMaterial, TerrainThresholds, GasGiantMaterial};
+use crate::clouds::CloudShell;
+use crate::rings::RingSystem;
+use crate::solar::SolarMaterial;
+use crate::texture::SurfaceTextures;
 use std::sync::Arc;
 use fastnoise_lite::FastNoiseLite;
 use nalgebra_glm::Mat4;
 
 pub struct Uniforms {
     pub model_matrix: Mat4,
+    pub mesh_center: Vec3, // Centro de la malla en su espacio local
+    pub view: ViewTransform,
+    pub time: f32,
+    pub noise: Arc<FastNoiseLite>,
+    pub lighting: SceneLighting,
+    pub appearance: Appearance,
+}
+
+/// Matrices de la cámara, las mismas para todo lo que se dibuja en un cuadro
+#[derive(Clone, Copy)]
+pub struct ViewTransform {
     pub view_matrix: Mat4,
     pub projection_matrix: Mat4,
     pub viewport_matrix: Mat4,
-    pub time: f32,
-    pub noise: Arc<FastNoiseLite>,
-    pub light_direction: Vec3,
-    pub noise_scale: f32,
-    pub ocean_threshold: f32,
-    pub continent_threshold: f32,
-    pub mountain_threshold: f32,
-    pub snow_threshold: f32,
-    pub ring_inner_radius: f32,
-    pub ring_outer_radius: f32,
-    pub ring_color: Color,
-    pub ring_opacity: f32,
-    pub ring_frequency: f32,
-    pub ring_wave_speed: f32,
-    pub ring_rotation_matrix: Mat4,
+}
+
+/// Luz que recibe un cuerpo, desde dónde se lo mira y qué le hace sombra
+#[derive(Clone)]
+pub struct SceneLighting {
+    pub light: PointLight,
+    pub camera_position: Vec3,
+    pub shadows: ShadowCasters,
+}
+
+/// Materiales y capas con los que se sombrea un cuerpo; los que no tiene quedan en `None`
+#[derive(Clone, Default)]
+pub struct Appearance {
+    pub terrain: Option<TerrainMaterial>,
+    pub thresholds: TerrainThresholds,
+    pub relief: f32,                   // Altura del relieve en la geometría (fracción del radio, ver `CelestialBody::relief`)
+    pub gas_giant: Option<GasGiantMaterial>,
+    pub solar: Option<SolarMaterial>,
+    pub textures: Option<SurfaceTextures>,
+    pub atmosphere: Option<AtmosphereShell>,
+    pub clouds: Option<CloudShell>,
+    pub rings: Option<RingSystem>,
 }
 
 impl Uniforms {
     pub fn new(
         model_matrix: Mat4,
-        view_matrix: Mat4,
-        projection_matrix: Mat4,
-        viewport_matrix: Mat4,
+        mesh_center: Vec3,
+        view: ViewTransform,
         time: f32,
         noise: Arc<FastNoiseLite>,
-        light_direction: Vec3,
-        noise_scale: f32,
-        ocean_threshold: f32,
-        continent_threshold: f32,
-        mountain_threshold: f32,
-        snow_threshold: f32,
-        ring_inner_radius: f32,
-        ring_outer_radius: f32,
-        ring_color: Color,
-        ring_opacity: f32,
-        ring_frequency: f32,
-        ring_wave_speed: f32,
-        ring_rotation_matrix: Mat4,
+        lighting: SceneLighting,
+        appearance: Appearance,
     ) -> Self {
         Uniforms {
             model_matrix,
+            mesh_center,
+            view,
+            time,
+            noise,
+            lighting,
+            appearance,
+        }
+    }
+}
+
+impl ViewTransform {
+    pub fn new(view_matrix: Mat4, projection_matrix: Mat4, viewport_matrix: Mat4) -> Self {
+        ViewTransform {
             view_matrix,
             projection_matrix,
             viewport_matrix,
-            time,
-            noise,
-            light_direction,
-            noise_scale,
-            ocean_threshold,
-            continent_threshold,
-            mountain_threshold,
-            snow_threshold,
-            ring_inner_radius,
-            ring_outer_radius,
-            ring_color,
-            ring_opacity,
-            ring_frequency,
-            ring_wave_speed,
-            ring_rotation_matrix,
+        }
+    }
+}
+
+impl SceneLighting {
+    pub fn new(light: PointLight, camera_position: Vec3, shadows: ShadowCasters) -> Self {
+        SceneLighting {
+            light,
+            camera_position,
+            shadows,
         }
     }
 }
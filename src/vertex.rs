@@ -8,6 +8,7 @@ pub struct Vertex {
     pub color: Color,
     pub transformed_position: Vec3,
     pub transformed_normal: Vec3,
+    pub world_position: Vec3,
 }
 
 impl Vertex {
@@ -19,6 +20,7 @@ impl Vertex {
             color: Color::black(),
             transformed_position: Vec3::zeros(),
             transformed_normal: Vec3::zeros(),
+            world_position: Vec3::zeros(),
         }
     }
 }